@@ -0,0 +1,267 @@
+//! CPU identification on aarch64, where there is no `cpuid`.
+//!
+//! The kernel prints the HWCAP/HWCAP2 bits it exposes to userspace, and
+//! each core's MIDR implementer and part numbers, in `/proc/cpuinfo`. Only
+//! what it prints is usable, so unlike on x86 every reported feature is.
+//! Caches come from sysfs (see [`crate::topology::caches`]).
+
+use std::fs;
+
+use crate::cpuid::{CpuFeature, FeatureCategory};
+
+/// What `/proc/cpuinfo` says about an aarch64 machine.
+#[derive(Debug, Default, PartialEq)]
+pub struct ArmCpuInfo {
+    /// HWCAP names, as the kernel prints them (`asimd`, `sve2`, ...).
+    pub hwcaps: Vec<String>,
+    /// Distinct `(implementer, part)` pairs, in CPU order. More than one on
+    /// big.LITTLE parts.
+    pub cores: Vec<(u32, u32)>,
+}
+
+fn parse_hex(value: &str) -> Option<u32> {
+    u32::from_str_radix(value.strip_prefix("0x")?, 16).ok()
+}
+
+impl ArmCpuInfo {
+    pub fn parse(cpuinfo: &str) -> Self {
+        let mut info = Self::default();
+        let mut implementer = None;
+        for line in cpuinfo.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                // Every CPU lists the same features.
+                "Features" if info.hwcaps.is_empty() => {
+                    info.hwcaps = value.split_whitespace().map(str::to_string).collect();
+                }
+                "CPU implementer" => implementer = parse_hex(value),
+                "CPU part" => {
+                    if let (Some(implementer), Some(part)) = (implementer, parse_hex(value)) {
+                        if !info.cores.contains(&(implementer, part)) {
+                            info.cores.push((implementer, part));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        info
+    }
+
+    /// Empty anywhere but aarch64 Linux.
+    pub fn read() -> Self {
+        if !cfg!(all(target_arch = "aarch64", target_os = "linux")) {
+            return Self::default();
+        }
+        fs::read_to_string("/proc/cpuinfo")
+            .map(|cpuinfo| Self::parse(&cpuinfo))
+            .unwrap_or_default()
+    }
+
+    /// The HWCAPs we know how to categorise; newer ones are left out until
+    /// they're added to [`HWCAPS`].
+    pub fn features(&self) -> Vec<CpuFeature> {
+        HWCAPS
+            .iter()
+            .filter(|(name, _)| self.hwcaps.iter().any(|hwcap| hwcap == name))
+            .map(|&(name, category)| CpuFeature {
+                name,
+                category,
+                usable: true,
+            })
+            .collect()
+    }
+
+    /// e.g. `["ARM Cortex-A55", "ARM Cortex-A76"]`.
+    pub fn core_names(&self) -> Vec<String> {
+        self.cores
+            .iter()
+            .map(|&(implementer, part)| core_name(implementer, part))
+            .collect()
+    }
+}
+
+const HWCAPS: &[(&str, FeatureCategory)] = {
+    use FeatureCategory::*;
+    &[
+        ("fp", Simd),
+        ("asimd", Simd),
+        ("fphp", Simd),
+        ("asimdhp", Simd),
+        ("asimdrdm", Simd),
+        ("asimddp", Simd),
+        ("asimdfhm", Simd),
+        ("fcma", Simd),
+        ("jscvt", Simd),
+        ("frint", Simd),
+        ("i8mm", Simd),
+        ("bf16", Simd),
+        ("sve", Simd),
+        ("sve2", Simd),
+        ("svei8mm", Simd),
+        ("svef32mm", Simd),
+        ("svef64mm", Simd),
+        ("svebf16", Simd),
+        ("svebitperm", Simd),
+        ("sme", Simd),
+        ("sme2", Simd),
+        ("aes", Crypto),
+        ("pmull", Crypto),
+        ("sha1", Crypto),
+        ("sha2", Crypto),
+        ("sha3", Crypto),
+        ("sha512", Crypto),
+        ("sm3", Crypto),
+        ("sm4", Crypto),
+        ("sveaes", Crypto),
+        ("svepmull", Crypto),
+        ("svesha3", Crypto),
+        ("svesm4", Crypto),
+        ("rng", Crypto),
+        ("paca", Security),
+        ("pacg", Security),
+        ("bti", Security),
+        ("mte", Security),
+        ("mte3", Security),
+        ("sb", Security),
+        ("ssbs", Security),
+        ("dit", Security),
+        ("atomics", Memory),
+        ("uscat", Memory),
+        ("lrcpc", Memory),
+        ("ilrcpc", Memory),
+        ("dcpop", Memory),
+        ("dcpodp", Memory),
+        ("flagm", Other),
+        ("flagm2", Other),
+        ("crc32", Other),
+        ("evtstrm", Other),
+        ("cpuid", Other),
+        ("ecv", Other),
+        ("afp", Other),
+        ("rpres", Other),
+        ("wfxt", Other),
+    ]
+};
+
+fn implementer_name(implementer: u32) -> Option<&'static str> {
+    Some(match implementer {
+        0x41 => "ARM",
+        0x42 => "Broadcom",
+        0x43 => "Cavium",
+        0x46 => "Fujitsu",
+        0x48 => "HiSilicon",
+        0x4e => "NVIDIA",
+        0x50 => "APM",
+        0x51 => "Qualcomm",
+        0x61 => "Apple",
+        0x6d => "Microsoft",
+        0xc0 => "Ampere",
+        _ => return None,
+    })
+}
+
+fn part_name(implementer: u32, part: u32) -> Option<&'static str> {
+    Some(match (implementer, part) {
+        (0x41, 0xd03) => "Cortex-A53",
+        (0x41, 0xd04) => "Cortex-A35",
+        (0x41, 0xd05) => "Cortex-A55",
+        (0x41, 0xd07) => "Cortex-A57",
+        (0x41, 0xd08) => "Cortex-A72",
+        (0x41, 0xd09) => "Cortex-A73",
+        (0x41, 0xd0a) => "Cortex-A75",
+        (0x41, 0xd0b) => "Cortex-A76",
+        (0x41, 0xd0c) => "Neoverse-N1",
+        (0x41, 0xd0d) => "Cortex-A77",
+        (0x41, 0xd40) => "Neoverse-V1",
+        (0x41, 0xd41) => "Cortex-A78",
+        (0x41, 0xd44) => "Cortex-X1",
+        (0x41, 0xd46) => "Cortex-A510",
+        (0x41, 0xd47) => "Cortex-A710",
+        (0x41, 0xd48) => "Cortex-X2",
+        (0x41, 0xd49) => "Neoverse-N2",
+        (0x41, 0xd4d) => "Cortex-A715",
+        (0x41, 0xd4e) => "Cortex-X3",
+        (0x41, 0xd4f) => "Neoverse-V2",
+        (0x41, 0xd80) => "Cortex-A520",
+        (0x41, 0xd81) => "Cortex-A720",
+        (0x41, 0xd82) => "Cortex-X4",
+        (0x41, 0xd8e) => "Neoverse-N3",
+        (0x46, 0x001) => "A64FX",
+        (0x48, 0xd01) => "TaiShan v110",
+        (0x4e, 0x004) => "Carmel",
+        (0x51, 0x800) => "Kryo 2xx Gold",
+        (0x51, 0x801) => "Kryo 2xx Silver",
+        (0x51, 0x802) => "Kryo 3xx Gold",
+        (0x51, 0x803) => "Kryo 3xx Silver",
+        (0x51, 0x804) => "Kryo 4xx Gold",
+        (0x51, 0x805) => "Kryo 4xx Silver",
+        (0x51, 0x001) => "Oryon",
+        (0x61, 0x022) => "M1 Icestorm",
+        (0x61, 0x023) => "M1 Firestorm",
+        (0x61, 0x032) => "M2 Blizzard",
+        (0x61, 0x033) => "M2 Avalanche",
+        (0xc0, 0xac3) => "Ampere-1",
+        (0xc0, 0xac4) => "Ampere-1a",
+        _ => return None,
+    })
+}
+
+/// A readable name for a core, falling back to the raw ids for parts we
+/// don't know.
+pub fn core_name(implementer: u32, part: u32) -> String {
+    match (implementer_name(implementer), part_name(implementer, part)) {
+        (Some(vendor), Some(part)) => format!("{vendor} {part}"),
+        (Some(vendor), None) => format!("{vendor} part {part:#05x}"),
+        (None, _) => format!("Implementer {implementer:#04x} part {part:#05x}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_features_and_big_little_cores() {
+        let cpuinfo = "\
+processor\t: 0
+BogoMIPS\t: 48.00
+Features\t: fp asimd evtstrm aes pmull sha1 sha2 crc32 atomics cpuid asimdrdm lrcpc dcpop asimddp
+CPU implementer\t: 0x41
+CPU architecture: 8
+CPU variant\t: 0x2
+CPU part\t: 0xd05
+CPU revision\t: 0
+
+processor\t: 1
+Features\t: fp asimd evtstrm aes pmull sha1 sha2 crc32 atomics cpuid asimdrdm lrcpc dcpop asimddp
+CPU implementer\t: 0x41
+CPU part\t: 0xd05
+
+processor\t: 2
+Features\t: fp asimd evtstrm aes pmull sha1 sha2 crc32 atomics cpuid asimdrdm lrcpc dcpop asimddp
+CPU implementer\t: 0x41
+CPU part\t: 0xd0b
+";
+        let info = ArmCpuInfo::parse(cpuinfo);
+        assert_eq!(info.cores, [(0x41, 0xd05), (0x41, 0xd0b)]);
+        assert_eq!(info.core_names(), ["ARM Cortex-A55", "ARM Cortex-A76"]);
+
+        let features = info.features();
+        let names: Vec<&str> = features.iter().map(|feature| feature.name).collect();
+        assert_eq!(
+            names,
+            [
+                "fp", "asimd", "asimdrdm", "asimddp", "aes", "pmull", "sha1", "sha2", "atomics",
+                "lrcpc", "dcpop", "crc32", "evtstrm", "cpuid"
+            ]
+        );
+        assert!(features.iter().all(|feature| feature.usable));
+
+        assert_eq!(core_name(0x51, 0xfff), "Qualcomm part 0xfff");
+        assert_eq!(core_name(0x99, 0x1), "Implementer 0x99 part 0x001");
+    }
+}
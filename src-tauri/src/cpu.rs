@@ -1,12 +1,21 @@
-use std::sync::atomic::Ordering;
+use std::path::Path;
 
 use serde::Serialize;
-use sysinfo::{CpuRefreshKind, RefreshKind, System, MINIMUM_CPU_UPDATE_INTERVAL};
-use tauri::{ipc::Channel, State};
+use sysinfo::{CpuRefreshKind, RefreshKind, System};
+use tauri::{ipc::Channel, State, Window};
 
 use crate::{
-    cpuid::{self, CacheInfo},
-    streams::{StreamName, StreamRegistry, SAMPLE_INTERVAL},
+    arm::ArmCpuInfo,
+    cpufreq::{self, FrequencyPolicy, FrequencyScaling},
+    cpuid::{self, CacheInfo, CoreType, CpuFeature, CpuSignature, TlbInfo},
+    cpuidle::IdleResidency,
+    cputime::CpuTimes,
+    hub::{Hub, Tick},
+    source::MetricsSource,
+    streams::{StreamName, StreamRegistry, SubscriberId},
+    sysfs,
+    topology::{self, LogicalCpu},
+    vulnerabilities::{self, Vulnerability},
 };
 
 #[derive(Debug, Serialize)]
@@ -15,13 +24,32 @@ pub struct CpuStatic {
     physical_cores: usize,
     logical_cores: usize,
     cache_sizes: Vec<CacheInfo>,
-    features: Vec<&'static str>,
+    /// x86 only, and only Intel reports them.
+    tlbs: Vec<TlbInfo>,
+    features: Vec<CpuFeature>,
+    /// Each distinct core design, decoded from MIDR; aarch64 Linux only.
+    core_names: Vec<String>,
+    /// Mixes performance and efficiency cores; see `LogicalCpu::core_type`.
+    hybrid: bool,
+    /// cpufreq policies; empty outside Linux.
+    frequency_policies: Vec<FrequencyPolicy>,
+    /// Whether turbo frequencies are enabled, if the driver can say.
+    boost: Option<bool>,
+    /// One entry per online logical CPU; empty outside Linux.
+    topology: Vec<LogicalCpu>,
+    /// x86 only.
+    signature: Option<CpuSignature>,
+    /// Linux only, like `vulnerabilities`.
+    microcode: Option<String>,
+    vulnerabilities: Vec<Vulnerability>,
 }
 
 #[tauri::command]
 pub fn get_cpu_static() -> CpuStatic {
     let sys =
         System::new_with_specifics(RefreshKind::nothing().with_cpu(CpuRefreshKind::nothing()));
+    let sysfs_root = Path::new(sysfs::ROOT);
+    let arm = ArmCpuInfo::read();
     CpuStatic {
         brand: sys
             .cpus()
@@ -30,8 +58,28 @@ pub fn get_cpu_static() -> CpuStatic {
             .unwrap_or_default(),
         physical_cores: sys.physical_core_count().unwrap_or(0),
         logical_cores: sys.cpus().len(),
-        cache_sizes: cpuid::cache_sizes(),
-        features: cpuid::features(),
+        cache_sizes: {
+            let caches = cpuid::cache_sizes();
+            if caches.is_empty() {
+                topology::caches(sysfs_root)
+            } else {
+                caches
+            }
+        },
+        tlbs: cpuid::tlbs(),
+        features: if cfg!(target_arch = "aarch64") {
+            arm.features()
+        } else {
+            cpuid::features()
+        },
+        core_names: arm.core_names(),
+        hybrid: cpuid::is_hybrid(),
+        frequency_policies: cpufreq::policies(sysfs_root),
+        boost: cpufreq::boost(sysfs_root),
+        topology: topology::topology(sysfs_root),
+        signature: cpuid::signature(),
+        microcode: vulnerabilities::microcode_revision(sysfs_root),
+        vulnerabilities: vulnerabilities::vulnerabilities(sysfs_root),
     }
 }
 
@@ -41,46 +89,70 @@ pub struct CoreSnapshot {
     usage: f32,
     /// Current frequency in MHz.
     frequency: u64,
+    /// Linux only; `None` elsewhere and until the CPUs were refreshed twice.
+    times: Option<CpuTimes>,
+    /// Governor and limits of the core's cpufreq policy; Linux only.
+    scaling: Option<FrequencyScaling>,
+    core_type: Option<CoreType>,
+    frequency_class: Option<u32>,
+    /// Only sent when the subscriber asked for idle states; see
+    /// [`get_cpu_info`].
+    idle_states: Option<Vec<IdleResidency>>,
 }
 
 #[derive(Debug, Serialize, Clone)]
 pub struct CpuSnapshot {
+    #[serde(flatten)]
+    tick: Tick,
     global_usage: f32,
+    global_times: Option<CpuTimes>,
     cores: Vec<CoreSnapshot>,
 }
 
+fn snapshot(source: &dyn MetricsSource, tick: Tick, idle_states: bool) -> CpuSnapshot {
+    CpuSnapshot {
+        tick,
+        global_usage: source.global_cpu_usage(),
+        global_times: source.global_cpu_times(),
+        cores: source
+            .cpus()
+            .into_iter()
+            .map(|cpu| CoreSnapshot {
+                name: cpu.name,
+                usage: cpu.usage,
+                frequency: cpu.frequency,
+                times: cpu.times,
+                scaling: cpu.scaling,
+                core_type: cpu.core_type,
+                frequency_class: cpu.frequency_class,
+                idle_states: idle_states.then_some(cpu.idle_states),
+            })
+            .collect(),
+    }
+}
+
 #[tauri::command]
-pub async fn get_cpu_info(
+pub fn get_cpu_info(
     registry: State<'_, StreamRegistry>,
+    hub: State<'_, Hub>,
+    window: Window,
     on_event: Channel<CpuSnapshot>,
-) -> Result<(), String> {
-    let cancelled = registry.begin(StreamName::Cpu);
-    let refresh = CpuRefreshKind::nothing().with_cpu_usage().with_frequency();
-    let mut sys = System::new_with_specifics(RefreshKind::nothing().with_cpu(refresh));
-
-    // Usage is a delta between two refreshes; wait so the first sample is real.
-    tokio::time::sleep(MINIMUM_CPU_UPDATE_INTERVAL).await;
-
-    while !cancelled.load(Ordering::Relaxed) {
-        sys.refresh_cpu_specifics(refresh);
-
-        let snapshot = CpuSnapshot {
-            global_usage: sys.global_cpu_usage(),
-            cores: sys
-                .cpus()
-                .iter()
-                .map(|cpu| CoreSnapshot {
-                    name: cpu.name().to_string(),
-                    usage: cpu.cpu_usage(),
-                    frequency: cpu.frequency(),
-                })
-                .collect(),
-        };
-
-        if on_event.send(snapshot).is_err() {
-            break; // Webview is gone; nobody is listening anymore.
-        }
-        tokio::time::sleep(SAMPLE_INTERVAL).await;
+    token: String,
+    interval_ms: Option<u64>,
+    idle_states: Option<bool>,
+) {
+    let stream = registry.begin(
+        StreamName::Cpu,
+        SubscriberId::new(&window, token),
+        interval_ms,
+    );
+    if idle_states.unwrap_or(false) {
+        hub.subscribe_with_idle_states(stream, on_event, |source, tick| {
+            snapshot(source, tick, true)
+        });
+    } else {
+        hub.subscribe(stream, on_event, |source, tick| {
+            snapshot(source, tick, false)
+        });
     }
-    Ok(())
 }
@@ -0,0 +1,339 @@
+//! Frequency scaling policies from the Linux cpufreq sysfs interface.
+//!
+//! Each `devices/system/cpu/cpufreq/policyN` directory governs one or more
+//! CPUs (listed in `affected_cpus`). Hardware limits, the driver and the
+//! time-in-state histogram are reported per policy by `get_cpu_static`; the
+//! governor and the limits it was given can change under us and are
+//! streamed per core. Frequencies are in kHz in sysfs and MHz here.
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{Error, Result},
+    sysfs, topology,
+};
+
+/// The fixed part of a policy.
+#[derive(Debug, Clone, Serialize)]
+pub struct FrequencyPolicy {
+    /// `policyN` directory name.
+    pub id: u32,
+    /// Logical CPU numbers it governs.
+    pub cpus: Vec<u32>,
+    /// e.g. `intel_pstate`, `amd-pstate-epp`, `acpi-cpufreq`.
+    pub driver: Option<String>,
+    pub available_governors: Vec<String>,
+    /// Hardware limits in MHz.
+    pub min_frequency: Option<u64>,
+    pub max_frequency: Option<u64>,
+    /// Guaranteed non-turbo frequency; only some drivers report it.
+    pub base_frequency: Option<u64>,
+    /// As of the call. Empty unless the kernel was built with
+    /// `CONFIG_CPU_FREQ_STAT`, and for drivers (like `intel_pstate` in
+    /// active mode) that don't keep it.
+    pub time_in_state: Vec<FrequencyResidency>,
+}
+
+/// The live part of a policy, as seen from one of its cores.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FrequencyScaling {
+    pub governor: Option<String>,
+    /// Limits the governor works within, in MHz.
+    pub scaling_min: Option<u64>,
+    pub scaling_max: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrequencyResidency {
+    /// MHz.
+    pub frequency: u64,
+    /// Milliseconds spent at this frequency since boot.
+    pub time_ms: u64,
+}
+
+fn cpufreq_dir(sysfs_root: &Path) -> PathBuf {
+    sysfs_root.join("devices/system/cpu/cpufreq")
+}
+
+fn read_mhz(path: &Path) -> Option<u64> {
+    sysfs::read_i64(path).and_then(|khz| u64::try_from(khz / 1000).ok())
+}
+
+fn read_list(path: &Path) -> Vec<String> {
+    sysfs::read_string(path)
+        .map(|list| list.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// The logical CPUs a policy governs.
+fn read_cpus(dir: &Path) -> Vec<u32> {
+    read_list(&dir.join("affected_cpus"))
+        .iter()
+        .filter_map(|cpu| cpu.parse().ok())
+        .collect()
+}
+
+/// `(id, directory)` of every policy, by id.
+fn policy_dirs(sysfs_root: &Path) -> Vec<(u32, PathBuf)> {
+    let Ok(entries) = fs::read_dir(cpufreq_dir(sysfs_root)) else {
+        return Vec::new();
+    };
+    let mut policies: Vec<(u32, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let id = name.strip_prefix("policy")?.parse().ok()?;
+            Some((id, entry.path()))
+        })
+        .collect();
+    policies.sort();
+    policies
+}
+
+pub fn policies(sysfs_root: &Path) -> Vec<FrequencyPolicy> {
+    policy_dirs(sysfs_root)
+        .into_iter()
+        .map(|(id, dir)| FrequencyPolicy {
+            id,
+            cpus: read_cpus(&dir),
+            driver: sysfs::read_string(&dir.join("scaling_driver")),
+            available_governors: read_list(&dir.join("scaling_available_governors")),
+            min_frequency: read_mhz(&dir.join("cpuinfo_min_freq")),
+            max_frequency: read_mhz(&dir.join("cpuinfo_max_freq")),
+            base_frequency: read_mhz(&dir.join("base_frequency")),
+            time_in_state: read_time_in_state(&dir),
+        })
+        .collect()
+}
+
+/// Ranks each CPU by its policy's maximum frequency: class 0 is the fastest,
+/// so on big.LITTLE-style parts it separates the core clusters.
+pub fn frequency_classes(policies: &[FrequencyPolicy]) -> HashMap<u32, u32> {
+    let mut maxima: Vec<u64> = policies
+        .iter()
+        .filter_map(|policy| policy.max_frequency)
+        .collect();
+    maxima.sort_unstable_by(|a, b| b.cmp(a));
+    maxima.dedup();
+    policies
+        .iter()
+        .filter_map(|policy| {
+            let max = policy.max_frequency?;
+            let class = maxima.iter().position(|&other| other == max)?;
+            Some((policy, u32::try_from(class).ok()?))
+        })
+        .flat_map(|(policy, class)| policy.cpus.iter().map(move |&cpu| (cpu, class)))
+        .collect()
+}
+
+/// Whether turbo/boost frequencies are allowed, or `None` if the driver
+/// has no switch for it.
+pub fn boost(sysfs_root: &Path) -> Option<bool> {
+    let cpufreq = cpufreq_dir(sysfs_root);
+    if let Some(boost) = sysfs::read_i64(&cpufreq.join("boost")) {
+        return Some(boost != 0);
+    }
+    // intel_pstate has its own, inverted switch.
+    let no_turbo = sysfs_root.join("devices/system/cpu/intel_pstate/no_turbo");
+    sysfs::read_i64(&no_turbo).map(|no_turbo| no_turbo == 0)
+}
+
+fn read_time_in_state(dir: &Path) -> Vec<FrequencyResidency> {
+    sysfs::read_string(&dir.join("stats/time_in_state"))
+        .map(|table| {
+            table
+                .lines()
+                .filter_map(|line| {
+                    let (khz, ticks) = line.split_once(' ')?;
+                    Some(FrequencyResidency {
+                        frequency: khz.trim().parse::<u64>().ok()? / 1000,
+                        // USER_HZ is 100 on every architecture we run on.
+                        time_ms: ticks.trim().parse::<u64>().ok()? * 10,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn read_scaling(dir: &Path) -> FrequencyScaling {
+    FrequencyScaling {
+        governor: sysfs::read_string(&dir.join("scaling_governor")),
+        scaling_min: read_mhz(&dir.join("scaling_min_freq")),
+        scaling_max: read_mhz(&dir.join("scaling_max_freq")),
+    }
+}
+
+/// Re-reads the live policy state on each refresh.
+pub struct CpufreqSampler {
+    root: PathBuf,
+    /// Per policy: the CPUs it governs and its current state.
+    policies: Vec<(Vec<u32>, FrequencyScaling)>,
+}
+
+impl CpufreqSampler {
+    pub fn new(sysfs_root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: sysfs_root.into(),
+            policies: Vec::new(),
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        if !cfg!(target_os = "linux") {
+            return;
+        }
+        self.policies = policy_dirs(&self.root)
+            .into_iter()
+            .map(|(_, dir)| (read_cpus(&dir), read_scaling(&dir)))
+            .collect();
+    }
+
+    /// The policy governing a CPU, by its sysinfo name (`cpu3`).
+    pub fn core(&self, name: &str) -> Option<FrequencyScaling> {
+        let cpu = topology::cpu_id(name)?;
+        self.policies
+            .iter()
+            .find(|(cpus, _)| cpus.contains(&cpu))
+            .map(|(_, scaling)| scaling.clone())
+    }
+}
+
+/// Switches one policy, or every policy, to `governor`.
+pub fn set_governor(sysfs_root: &Path, governor: &str, policy: Option<u32>) -> Result<()> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::Unsupported {
+            feature: "Changing the CPU governor".to_string(),
+        });
+    }
+    let targets: Vec<(u32, PathBuf)> = policy_dirs(sysfs_root)
+        .into_iter()
+        .filter(|(id, _)| policy.is_none_or(|policy| policy == *id))
+        .collect();
+    if targets.is_empty() {
+        return Err(Error::NotFound {
+            what: match policy {
+                Some(policy) => format!("cpufreq policy {policy}"),
+                None => "cpufreq".to_string(),
+            },
+        });
+    }
+    // Check everything first so a bad name doesn't leave the policies
+    // half switched.
+    for (id, dir) in &targets {
+        if !read_list(&dir.join("scaling_available_governors")).contains(&governor.to_string()) {
+            return Err(Error::NotFound {
+                what: format!("Governor {governor:?} for cpufreq policy {id}"),
+            });
+        }
+    }
+    for (id, dir) in &targets {
+        let path = dir.join("scaling_governor");
+        fs::write(&path, governor).map_err(|err| match err.kind() {
+            ErrorKind::PermissionDenied => Error::PermissionDenied {
+                action: format!("set the governor of cpufreq policy {id}"),
+            },
+            _ => Error::io_at(&path, err),
+        })?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_cpu_governor(governor: String, policy: Option<u32>) -> Result<()> {
+    set_governor(Path::new(sysfs::ROOT), &governor, policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::Fixture;
+
+    fn write_policies(fixture: &Fixture) {
+        let cpufreq = Path::new("devices/system/cpu/cpufreq");
+        let write = |path: PathBuf, contents| fixture.write(path, contents);
+        for (id, cpus) in [(0, "0 1"), (2, "2 3")] {
+            let policy = cpufreq.join(format!("policy{id}"));
+            write(policy.join("affected_cpus"), cpus);
+            write(policy.join("scaling_driver"), "acpi-cpufreq");
+            write(
+                policy.join("scaling_available_governors"),
+                "performance schedutil",
+            );
+            write(policy.join("scaling_governor"), "schedutil");
+            write(policy.join("cpuinfo_min_freq"), "400000");
+            write(policy.join("cpuinfo_max_freq"), "4700000");
+            write(policy.join("scaling_min_freq"), "400000");
+            write(policy.join("scaling_max_freq"), "3600000");
+            write(
+                policy.join("stats/time_in_state"),
+                "400000 1500\n3600000 25",
+            );
+        }
+        write(cpufreq.join("boost"), "1");
+    }
+
+    #[test]
+    fn reads_policies() {
+        let fixture = Fixture::new("cpufreq");
+        let root = fixture.root();
+        write_policies(&fixture);
+
+        let static_policies = policies(root);
+        assert_eq!(static_policies.len(), 2);
+        assert_eq!(static_policies[1].cpus, [2, 3]);
+        assert_eq!(static_policies[1].max_frequency, Some(4700));
+        assert_eq!(static_policies[1].base_frequency, None);
+        assert_eq!(
+            static_policies[1].time_in_state,
+            [
+                FrequencyResidency {
+                    frequency: 400,
+                    time_ms: 15_000,
+                },
+                FrequencyResidency {
+                    frequency: 3600,
+                    time_ms: 250,
+                },
+            ]
+        );
+        assert_eq!(boost(root), Some(true));
+
+        let (_, policy2) = &policy_dirs(root)[1];
+        let scaling = read_scaling(policy2);
+        assert_eq!(scaling.governor.as_deref(), Some("schedutil"));
+        assert_eq!(scaling.scaling_max, Some(3600));
+    }
+
+    // The sampler and `set_governor` only touch sysfs on Linux.
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn switches_governors() {
+        let fixture = Fixture::new("cpufreq-governors");
+        let root = fixture.root();
+        write_policies(&fixture);
+
+        let mut sampler = CpufreqSampler::new(root);
+        sampler.refresh();
+        let cpu3 = sampler.core("cpu3").expect("cpu3 is in policy2");
+        assert_eq!(cpu3.governor.as_deref(), Some("schedutil"));
+        assert_eq!(sampler.core("cpu4"), None);
+
+        assert!(matches!(
+            set_governor(root, "powersave", None),
+            Err(Error::NotFound { .. })
+        ));
+        set_governor(root, "performance", Some(2)).expect("switch policy2");
+        sampler.refresh();
+        let governor = |cpu: &str| sampler.core(cpu).and_then(|scaling| scaling.governor);
+        assert_eq!(governor("cpu0").as_deref(), Some("schedutil"));
+        assert_eq!(governor("cpu2").as_deref(), Some("performance"));
+    }
+}
@@ -1,21 +1,106 @@
 //! CPU cache/feature detection via `cpuid`.
 //!
-//! Only meaningful on x86_64; other architectures get empty results and the
-//! frontend hides the corresponding sections.
+//! Only meaningful on x86_64; other architectures get empty results and
+//! `get_cpu_static` falls back to sysfs for caches and, on aarch64, to
+//! [`crate::arm`] for features. Hybrid core types are the
+//! exception: `cpuid` leaf 0x1A only describes the core executing it, so
+//! which CPU is which comes from the per-type PMUs the kernel registers.
 
-use serde::Serialize;
+use std::{collections::HashMap, path::Path};
 
-#[derive(Debug, Serialize, Clone)]
+use serde::{Deserialize, Serialize};
+
+use crate::sysfs;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
 pub struct CacheInfo {
     pub label: String,
     pub bytes: usize,
+    pub level: u8,
+    /// Ways of associativity. The geometry fields are `None` when sysfs
+    /// doesn't report them, as on many device-tree platforms.
+    pub associativity: Option<usize>,
+    /// Coherency line size in bytes.
+    pub line_size: Option<usize>,
+    pub sets: Option<usize>,
+    /// Physical line partitions per way.
+    pub partitions: Option<usize>,
+    /// Logical CPUs sharing one instance. From `cpuid` this is the most the
+    /// cache is designed for, which can exceed the CPUs actually present.
+    pub shared_by: usize,
+    /// Whether it also holds everything the lower levels do; `cpuid` only.
+    pub inclusive: Option<bool>,
+    pub fully_associative: bool,
+}
+
+/// One translation lookaside buffer.
+#[derive(Debug, Serialize, Clone)]
+pub struct TlbInfo {
+    /// e.g. "L1 Data TLB", or the manual's description of a leaf 2
+    /// descriptor on CPUs without leaf 0x18, in which case the other
+    /// fields are unknown.
+    pub label: String,
+    pub level: Option<u8>,
+    /// Page sizes it holds entries for: "4K", "2M", "4M", "1G".
+    pub page_sizes: Vec<&'static str>,
+    pub entries: Option<usize>,
+    pub associativity: Option<usize>,
+    pub fully_associative: bool,
+    /// The most logical CPUs that can share it.
+    pub shared_by: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FeatureCategory {
+    Simd,
+    Crypto,
+    Virtualization,
+    Security,
+    Memory,
+    Other,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CpuFeature {
+    pub name: &'static str,
+    pub category: FeatureCategory,
+    /// False when the hardware has it but the OS hasn't enabled the register
+    /// state it needs (e.g. AVX-512 with ZMM state masked off in XCR0).
+    pub usable: bool,
+}
+
+/// Identifies the exact CPU revision, as errata and microcode updates are
+/// published against it. Family and model include the extended fields.
+#[derive(Debug, Serialize, Clone)]
+pub struct CpuSignature {
+    /// e.g. "GenuineIntel", "AuthenticAMD".
+    pub vendor: String,
+    pub family: u32,
+    pub model: u32,
+    pub stepping: u32,
 }
 
 #[cfg(target_arch = "x86_64")]
 mod x86 {
-    use raw_cpuid::{CacheType, CpuId};
+    use raw_cpuid::{CacheInfoType, CacheType, CpuId, DatType, Hypervisor};
+
+    use super::{CacheInfo, CpuFeature, CpuSignature, FeatureCategory, TlbInfo};
 
-    use super::CacheInfo;
+    /// From leaf 1.
+    pub fn signature() -> Option<CpuSignature> {
+        let cpuid = CpuId::new();
+        let info = cpuid.get_feature_info()?;
+        Some(CpuSignature {
+            vendor: cpuid
+                .get_vendor_info()
+                .map(|vendor| vendor.as_str().to_string())
+                .unwrap_or_default(),
+            family: info.family_id().into(),
+            model: info.model_id().into(),
+            stepping: info.stepping_id().into(),
+        })
+    }
 
     pub fn cache_sizes() -> Vec<CacheInfo> {
         let Some(cparams) = CpuId::new().get_cache_parameters() else {
@@ -36,127 +121,349 @@ mod x86 {
                 CacheInfo {
                     label: format!("L{} {kind} Cache", cache.level()),
                     bytes,
+                    level: cache.level(),
+                    associativity: Some(cache.associativity()),
+                    line_size: Some(cache.coherency_line_size()),
+                    sets: Some(cache.sets()),
+                    partitions: Some(cache.physical_line_partitions()),
+                    shared_by: cache.max_cores_for_cache(),
+                    inclusive: Some(cache.is_inclusive()),
+                    fully_associative: cache.is_fully_associative(),
                 }
             })
             .collect()
     }
 
+    /// From leaf 0x18 where the CPU has it (Intel since Skylake), otherwise
+    /// the TLB descriptors in leaf 2. AMD reports neither.
+    pub fn tlbs() -> Vec<TlbInfo> {
+        let cpuid = CpuId::new();
+        if let Some(dat) = cpuid.get_deterministic_address_translation_info() {
+            let tlbs: Vec<TlbInfo> = dat
+                .map(|tlb| {
+                    let kind = match tlb.cache_type() {
+                        DatType::DataTLB => "Data",
+                        DatType::InstructionTLB => "Instruction",
+                        DatType::UnifiedTLB => "Unified",
+                        DatType::LoadOnly => "Load",
+                        DatType::StoreOnly => "Store",
+                        _ => "Unknown",
+                    };
+                    let page_sizes = [
+                        (tlb.has_4k_entries(), "4K"),
+                        (tlb.has_2mb_entries(), "2M"),
+                        (tlb.has_4mb_entries(), "4M"),
+                        (tlb.has_1gb_entries(), "1G"),
+                    ]
+                    .into_iter()
+                    .filter_map(|(has, size)| has.then_some(size))
+                    .collect();
+                    let ways = usize::from(tlb.ways());
+                    TlbInfo {
+                        label: format!("L{} {kind} TLB", tlb.cache_level()),
+                        level: Some(tlb.cache_level()),
+                        page_sizes,
+                        entries: Some(ways * tlb.sets() as usize),
+                        associativity: Some(ways),
+                        fully_associative: tlb.is_fully_associative(),
+                        shared_by: Some(tlb.max_addressable_ids().into()),
+                    }
+                })
+                .collect();
+            if !tlbs.is_empty() {
+                return tlbs;
+            }
+        }
+        let Some(descriptors) = cpuid.get_cache_info() else {
+            return Vec::new();
+        };
+        descriptors
+            .filter(|descriptor| {
+                matches!(
+                    descriptor.typ,
+                    CacheInfoType::TLB | CacheInfoType::STLB | CacheInfoType::DTLB
+                )
+            })
+            .map(|descriptor| TlbInfo {
+                label: descriptor.desc().to_string(),
+                level: None,
+                page_sizes: Vec::new(),
+                entries: None,
+                associativity: None,
+                fully_associative: descriptor.desc().contains("fully associative"),
+                shared_by: None,
+            })
+            .collect()
+    }
+
+    /// From the hypervisor bit in leaf 1 and the vendor string in leaf
+    /// 0x4000_0000.
+    pub fn hypervisor() -> Option<String> {
+        let cpuid = CpuId::new();
+        if !cpuid.get_feature_info()?.has_hypervisor() {
+            return None;
+        }
+        let name = match cpuid.get_hypervisor_info().map(|info| info.identify()) {
+            Some(Hypervisor::Xen) => "Xen",
+            Some(Hypervisor::VMware) => "VMware",
+            Some(Hypervisor::HyperV) => "Hyper-V",
+            Some(Hypervisor::KVM) => "KVM",
+            Some(Hypervisor::QEMU) => "QEMU",
+            Some(Hypervisor::Bhyve) => "bhyve",
+            Some(Hypervisor::QNX) => "QNX",
+            Some(Hypervisor::ACRN) => "ACRN",
+            Some(Hypervisor::Unknown(ebx, ecx, edx)) => {
+                let vendor: Vec<u8> = [ebx, ecx, edx]
+                    .iter()
+                    .flat_map(|register| register.to_le_bytes())
+                    .collect();
+                let vendor = String::from_utf8_lossy(&vendor);
+                let vendor = vendor.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+                return Some(if vendor.is_empty() { "Unknown" } else { vendor }.to_string());
+            }
+            None => "Unknown",
+        };
+        Some(name.to_string())
+    }
+
+    /// CPUID.07H:EDX[15], which raw-cpuid doesn't decode.
+    pub fn is_hybrid() -> bool {
+        raw_cpuid::cpuid!(0).eax >= 7 && raw_cpuid::cpuid!(7, 0).edx & (1 << 15) != 0
+    }
+
+    /// XCR0 bits for register state the OS has to save on context switch.
+    /// Until it sets them, using the matching instructions faults even
+    /// though `cpuid` advertises them.
+    const XCR0_SSE: u64 = 1 << 1;
+    const XCR0_AVX: u64 = 1 << 2;
+    /// Opmask, ZMM_Hi256 and Hi16_ZMM.
+    const XCR0_AVX512: u64 = 0b111 << 5;
+    /// XTILECFG and XTILEDATA.
+    const XCR0_AMX: u64 = 0b11 << 17;
+
+    /// Register state a feature needs enabled to be usable.
+    #[derive(Clone, Copy)]
+    enum State {
+        /// Always usable when present.
+        Legacy,
+        Avx,
+        Avx512,
+        Amx,
+    }
+
+    impl State {
+        fn xcr0_mask(self) -> u64 {
+            match self {
+                Self::Legacy => 0,
+                Self::Avx => XCR0_SSE | XCR0_AVX,
+                Self::Avx512 => XCR0_SSE | XCR0_AVX | XCR0_AVX512,
+                Self::Amx => XCR0_AMX,
+            }
+        }
+    }
+
+    /// What the OS enabled in XCR0, or 0 if it doesn't use XSAVE at all.
+    fn xcr0() -> u64 {
+        let os_xsave = CpuId::new()
+            .get_feature_info()
+            .is_some_and(|info| info.has_oxsave());
+        if !os_xsave {
+            return 0;
+        }
+        // SAFETY: XGETBV is available whenever CR4.OSXSAVE is set, which
+        // is what the OSXSAVE flag reports.
+        unsafe { read_xcr0() }
+    }
+
+    #[target_feature(enable = "xsave")]
+    unsafe fn read_xcr0() -> u64 {
+        std::arch::x86_64::_xgetbv(0)
+    }
+
     macro_rules! collect_features {
-        ($info:expr, $features:ident, [$(($has:ident, $name:literal)),* $(,)?]) => {
-            $( if $info.$has() { $features.push($name); } )*
+        ($info:expr, $features:ident, $xcr0:expr, [$(($has:ident, $name:literal, $category:ident $(, $state:ident)?)),* $(,)?]) => {
+            $( if $info.$has() {
+                let state = [$(State::$state,)? State::Legacy][0];
+                $features.push(CpuFeature {
+                    name: $name,
+                    category: FeatureCategory::$category,
+                    usable: $xcr0 & state.xcr0_mask() == state.xcr0_mask(),
+                });
+            } )*
         };
     }
 
-    pub fn features() -> Vec<&'static str> {
+    pub fn features() -> Vec<CpuFeature> {
         let cpuid = CpuId::new();
-        let mut features = Vec::with_capacity(96);
+        let xcr0 = xcr0();
+        let mut features = Vec::with_capacity(160);
 
         if let Some(info) = cpuid.get_feature_info() {
             collect_features!(
                 info,
                 features,
+                xcr0,
                 [
-                    (has_sse3, "sse3"),
-                    (has_pclmulqdq, "pclmulqdq"),
-                    (has_ds_area, "ds_area"),
-                    (has_monitor_mwait, "monitor_mwait"),
-                    (has_cpl, "cpl"),
-                    (has_vmx, "vmx"),
-                    (has_smx, "smx"),
-                    (has_eist, "eist"),
-                    (has_tm2, "tm2"),
-                    (has_ssse3, "ssse3"),
-                    (has_cnxtid, "cnxtid"),
-                    (has_fma, "fma"),
-                    (has_cmpxchg16b, "cmpxchg16b"),
-                    (has_pdcm, "pdcm"),
-                    (has_pcid, "pcid"),
-                    (has_dca, "dca"),
-                    (has_sse41, "sse41"),
-                    (has_sse42, "sse42"),
-                    (has_x2apic, "x2apic"),
-                    (has_movbe, "movbe"),
-                    (has_popcnt, "popcnt"),
-                    (has_tsc_deadline, "tsc_deadline"),
-                    (has_aesni, "aesni"),
-                    (has_xsave, "xsave"),
-                    (has_oxsave, "oxsave"),
-                    (has_avx, "avx"),
-                    (has_f16c, "f16c"),
-                    (has_rdrand, "rdrand"),
-                    (has_fpu, "fpu"),
-                    (has_vme, "vme"),
-                    (has_de, "de"),
-                    (has_pse, "pse"),
-                    (has_tsc, "tsc"),
-                    (has_msr, "msr"),
-                    (has_pae, "pae"),
-                    (has_mce, "mce"),
-                    (has_cmpxchg8b, "cmpxchg8b"),
-                    (has_apic, "apic"),
-                    (has_sysenter_sysexit, "sysenter_sysexit"),
-                    (has_mtrr, "mtrr"),
-                    (has_pge, "pge"),
-                    (has_mca, "mca"),
-                    (has_cmov, "cmov"),
-                    (has_pat, "pat"),
-                    (has_pse36, "pse36"),
-                    (has_psn, "psn"),
-                    (has_clflush, "clflush"),
-                    (has_ds, "ds"),
-                    (has_acpi, "acpi"),
-                    (has_mmx, "mmx"),
-                    (has_fxsave_fxstor, "fxsave_fxstor"),
-                    (has_sse, "sse"),
-                    (has_sse2, "sse2"),
-                    (has_ss, "ss"),
-                    (has_htt, "htt"),
-                    (has_tm, "tm"),
-                    (has_pbe, "pbe"),
+                    (has_sse3, "sse3", Simd),
+                    (has_pclmulqdq, "pclmulqdq", Crypto),
+                    (has_ds_area, "ds_area", Other),
+                    (has_monitor_mwait, "monitor_mwait", Other),
+                    (has_cpl, "cpl", Other),
+                    (has_vmx, "vmx", Virtualization),
+                    (has_smx, "smx", Security),
+                    (has_eist, "eist", Other),
+                    (has_tm2, "tm2", Other),
+                    (has_ssse3, "ssse3", Simd),
+                    (has_cnxtid, "cnxtid", Other),
+                    (has_fma, "fma", Simd, Avx),
+                    (has_cmpxchg16b, "cmpxchg16b", Other),
+                    (has_pdcm, "pdcm", Other),
+                    (has_pcid, "pcid", Memory),
+                    (has_dca, "dca", Memory),
+                    (has_sse41, "sse41", Simd),
+                    (has_sse42, "sse42", Simd),
+                    (has_x2apic, "x2apic", Other),
+                    (has_movbe, "movbe", Other),
+                    (has_popcnt, "popcnt", Other),
+                    (has_tsc_deadline, "tsc_deadline", Other),
+                    (has_aesni, "aesni", Crypto),
+                    (has_xsave, "xsave", Other),
+                    (has_oxsave, "oxsave", Other),
+                    (has_avx, "avx", Simd, Avx),
+                    (has_f16c, "f16c", Simd, Avx),
+                    (has_rdrand, "rdrand", Crypto),
+                    (has_fpu, "fpu", Other),
+                    (has_vme, "vme", Virtualization),
+                    (has_de, "de", Other),
+                    (has_pse, "pse", Memory),
+                    (has_tsc, "tsc", Other),
+                    (has_msr, "msr", Other),
+                    (has_pae, "pae", Memory),
+                    (has_mce, "mce", Other),
+                    (has_cmpxchg8b, "cmpxchg8b", Other),
+                    (has_apic, "apic", Other),
+                    (has_sysenter_sysexit, "sysenter_sysexit", Other),
+                    (has_mtrr, "mtrr", Memory),
+                    (has_pge, "pge", Memory),
+                    (has_mca, "mca", Other),
+                    (has_cmov, "cmov", Other),
+                    (has_pat, "pat", Memory),
+                    (has_pse36, "pse36", Memory),
+                    (has_psn, "psn", Security),
+                    (has_clflush, "clflush", Memory),
+                    (has_ds, "ds", Other),
+                    (has_acpi, "acpi", Other),
+                    (has_mmx, "mmx", Simd),
+                    (has_fxsave_fxstor, "fxsave_fxstor", Other),
+                    (has_sse, "sse", Simd),
+                    (has_sse2, "sse2", Simd),
+                    (has_ss, "ss", Other),
+                    (has_htt, "htt", Other),
+                    (has_tm, "tm", Other),
+                    (has_pbe, "pbe", Other),
                 ]
             );
         }
 
+        // Leaf 7, including the ECX/EDX words and subleaf 1.
         if let Some(info) = cpuid.get_extended_feature_info() {
             collect_features!(
                 info,
                 features,
+                xcr0,
+                [
+                    (has_fsgsbase, "fsgsbase", Other),
+                    (has_bmi1, "bmi1", Other),
+                    (has_hle, "hle", Memory),
+                    (has_avx2, "avx2", Simd, Avx),
+                    (has_fdp, "fdp", Other),
+                    (has_smep, "smep", Security),
+                    (has_bmi2, "bmi2", Other),
+                    (has_rep_movsb_stosb, "rep_movsb_stosb", Memory),
+                    (has_invpcid, "invpcid", Memory),
+                    (has_rtm, "rtm", Memory),
+                    (has_rdtm, "rdtm", Other),
+                    (has_fpu_cs_ds_deprecated, "fpu_cs_ds_deprecated", Other),
+                    (has_mpx, "mpx", Security),
+                    (has_rdta, "rdta", Other),
+                    (has_rdseed, "rdseed", Crypto),
+                    (has_adx, "adx", Other),
+                    (has_smap, "smap", Security),
+                    (has_clflushopt, "clflushopt", Memory),
+                    (has_processor_trace, "processor_trace", Other),
+                    (has_sha, "sha", Crypto),
+                    (has_sgx, "sgx", Security),
+                    (has_avx512f, "avx512f", Simd, Avx512),
+                    (has_avx512dq, "avx512dq", Simd, Avx512),
+                    (has_avx512_ifma, "avx512_ifma", Simd, Avx512),
+                    (has_avx512pf, "avx512pf", Simd, Avx512),
+                    (has_avx512er, "avx512er", Simd, Avx512),
+                    (has_avx512cd, "avx512cd", Simd, Avx512),
+                    (has_avx512bw, "avx512bw", Simd, Avx512),
+                    (has_avx512vl, "avx512vl", Simd, Avx512),
+                    (has_clwb, "clwb", Memory),
+                    (has_prefetchwt1, "prefetchwt1", Memory),
+                    (has_avx512vbmi, "avx512vbmi", Simd, Avx512),
+                    (has_umip, "umip", Security),
+                    (has_pku, "pku", Security),
+                    (has_ospke, "ospke", Security),
+                    (has_waitpkg, "waitpkg", Other),
+                    (has_avx512vbmi2, "avx512vbmi2", Simd, Avx512),
+                    (has_cet_ss, "cet_ss", Security),
+                    (has_gfni, "gfni", Simd),
+                    (has_vaes, "vaes", Crypto, Avx),
+                    (has_vpclmulqdq, "vpclmulqdq", Crypto, Avx),
+                    (has_avx512vnni, "avx512vnni", Simd, Avx512),
+                    (has_avx512bitalg, "avx512bitalg", Simd, Avx512),
+                    (has_tme_en, "tme_en", Security),
+                    (has_avx512vpopcntdq, "avx512vpopcntdq", Simd, Avx512),
+                    (has_la57, "la57", Memory),
+                    (has_rdpid, "rdpid", Other),
+                    (has_sgx_lc, "sgx_lc", Security),
+                    (has_avx512_4vnniw, "avx512_4vnniw", Simd, Avx512),
+                    (has_avx512_4fmaps, "avx512_4fmaps", Simd, Avx512),
+                    (has_avx512_vp2intersect, "avx512_vp2intersect", Simd, Avx512),
+                    (has_amx_bf16, "amx_bf16", Simd, Amx),
+                    (has_avx512_fp16, "avx512_fp16", Simd, Avx512),
+                    (has_amx_tile, "amx_tile", Simd, Amx),
+                    (has_amx_int8, "amx_int8", Simd, Amx),
+                    (has_avx_vnni, "avx_vnni", Simd, Avx),
+                    (has_avx512_bf16, "avx512_bf16", Simd, Avx512),
+                    (has_fzrm, "fzrm", Memory),
+                    (has_fsrs, "fsrs", Memory),
+                    (has_fsrcrs, "fsrcrs", Memory),
+                    (has_hreset, "hreset", Other),
+                    (has_avx_ifma, "avx_ifma", Simd, Avx),
+                    (has_lam, "lam", Security),
+                    (has_avx_vnni_int8, "avx_vnni_int8", Simd, Avx),
+                    (has_avx_ne_convert, "avx_ne_convert", Simd, Avx),
+                    (has_avx_vnni_int16, "avx_vnni_int16", Simd, Avx),
+                    (has_cet_sss, "cet_sss", Security),
+                ]
+            );
+        }
+
+        // Leaf 0x8000_0001, mostly AMD additions.
+        if let Some(info) = cpuid.get_extended_processor_and_feature_identifiers() {
+            collect_features!(
+                info,
+                features,
+                xcr0,
                 [
-                    (has_bmi1, "bmi1"),
-                    (has_hle, "hle"),
-                    (has_avx2, "avx2"),
-                    (has_fdp, "fdp"),
-                    (has_smep, "smep"),
-                    (has_bmi2, "bmi2"),
-                    (has_rep_movsb_stosb, "rep_movsb_stosb"),
-                    (has_invpcid, "invpcid"),
-                    (has_rtm, "rtm"),
-                    (has_rdtm, "rdtm"),
-                    (has_fpu_cs_ds_deprecated, "fpu_cs_ds_deprecated"),
-                    (has_mpx, "mpx"),
-                    (has_rdta, "rdta"),
-                    (has_rdseed, "rdseed"),
-                    (has_adx, "adx"),
-                    (has_smap, "smap"),
-                    (has_clflushopt, "clflushopt"),
-                    (has_processor_trace, "processor_trace"),
-                    (has_sha, "sha"),
-                    (has_sgx, "sgx"),
-                    (has_avx512f, "avx512f"),
-                    (has_avx512dq, "avx512dq"),
-                    (has_avx512_ifma, "avx512_ifma"),
-                    (has_avx512pf, "avx512pf"),
-                    (has_avx512er, "avx512er"),
-                    (has_avx512cd, "avx512cd"),
-                    (has_avx512bw, "avx512bw"),
-                    (has_avx512vl, "avx512vl"),
-                    (has_clwb, "clwb"),
-                    (has_prefetchwt1, "prefetchwt1"),
-                    (has_umip, "umip"),
-                    (has_pku, "pku"),
-                    (has_ospke, "ospke"),
-                    (has_rdpid, "rdpid"),
-                    (has_sgx_lc, "sgx_lc"),
+                    (has_lahf_sahf, "lahf_sahf", Other),
+                    (has_svm, "svm", Virtualization),
+                    (has_lzcnt, "lzcnt", Other),
+                    (has_sse4a, "sse4a", Simd),
+                    (has_prefetchw, "prefetchw", Memory),
+                    (has_xop, "xop", Simd, Avx),
+                    (has_skinit, "skinit", Security),
+                    (has_fma4, "fma4", Simd, Avx),
+                    (has_tbm, "tbm", Other),
+                    (has_syscall_sysret, "syscall_sysret", Other),
+                    (has_execute_disable, "execute_disable", Security),
+                    (has_1gib_pages, "1gib_pages", Memory),
+                    (has_rdtscp, "rdtscp", Other),
+                    (has_64bit_mode, "64bit_mode", Other),
                 ]
             );
         }
@@ -166,7 +473,7 @@ mod x86 {
 }
 
 #[cfg(target_arch = "x86_64")]
-pub use x86::{cache_sizes, features};
+pub use x86::{cache_sizes, features, hypervisor, is_hybrid, signature, tlbs};
 
 #[cfg(not(target_arch = "x86_64"))]
 pub fn cache_sizes() -> Vec<CacheInfo> {
@@ -174,6 +481,54 @@ pub fn cache_sizes() -> Vec<CacheInfo> {
 }
 
 #[cfg(not(target_arch = "x86_64"))]
-pub fn features() -> Vec<&'static str> {
+pub fn tlbs() -> Vec<TlbInfo> {
     Vec::new()
 }
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn features() -> Vec<CpuFeature> {
+    Vec::new()
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn signature() -> Option<CpuSignature> {
+    None
+}
+
+/// The hypervisor we run under, or `None` on bare metal.
+#[cfg(not(target_arch = "x86_64"))]
+pub fn hypervisor() -> Option<String> {
+    None
+}
+
+/// Whether the CPU mixes core types (Intel Alder Lake and later).
+#[cfg(not(target_arch = "x86_64"))]
+pub fn is_hybrid() -> bool {
+    false
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoreType {
+    Performance,
+    Efficiency,
+}
+
+/// The type of each logical CPU on a hybrid part, from the `cpu_core` and
+/// `cpu_atom` PMUs' CPU lists. Empty when the CPU isn't hybrid or the
+/// kernel predates hybrid support (5.13).
+pub fn core_types(sysfs_root: &Path) -> HashMap<u32, CoreType> {
+    [
+        ("cpu_core", CoreType::Performance),
+        ("cpu_atom", CoreType::Efficiency),
+    ]
+    .into_iter()
+    .flat_map(|(pmu, core_type)| {
+        let cpus = sysfs::read_string(&sysfs_root.join("devices").join(pmu).join("cpus"))
+            .unwrap_or_default();
+        sysfs::parse_cpu_list(&cpus)
+            .into_iter()
+            .map(move |cpu| (cpu, core_type))
+    })
+    .collect()
+}
@@ -0,0 +1,184 @@
+//! Idle state (C-state) residency from the Linux cpuidle sysfs interface.
+//!
+//! Each `devices/system/cpu/cpuN/cpuidle/stateK` reports the total time the
+//! CPU has spent in that state since boot, in microseconds. Residency is the
+//! share of wall time between two refreshes spent there, so a core that
+//! never gets past a shallow state shows up even while it is mostly idle.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{sysfs, topology};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdleResidency {
+    /// e.g. "POLL", "C1E", "C6".
+    pub name: String,
+    /// Exit latency in microseconds; deeper states take longer to leave.
+    pub latency: u64,
+    /// Percent of the time since the previous refresh.
+    pub residency: f32,
+    /// Turned off through its `disable` switch, so it can't be entered.
+    pub disabled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct IdleState {
+    name: String,
+    latency: u64,
+    /// Microseconds since boot.
+    time: u64,
+    disabled: bool,
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    sysfs::read_i64(path).and_then(|value| u64::try_from(value).ok())
+}
+
+/// Every CPU's states, by CPU id. CPUs without cpuidle (no driver, or
+/// `cpuidle.off=1`) are left out.
+fn read_states(sysfs_root: &Path) -> HashMap<u32, Vec<IdleState>> {
+    topology::numbered_dirs(&sysfs_root.join("devices/system/cpu"), "cpu")
+        .into_iter()
+        .map(|(id, dir)| {
+            let states = topology::numbered_dirs(&dir.join("cpuidle"), "state")
+                .into_iter()
+                .filter_map(|(_, state)| {
+                    Some(IdleState {
+                        name: sysfs::read_string(&state.join("name"))?,
+                        latency: read_u64(&state.join("latency")).unwrap_or(0),
+                        time: read_u64(&state.join("time"))?,
+                        disabled: read_u64(&state.join("disable")).is_some_and(|off| off != 0),
+                    })
+                })
+                .collect::<Vec<_>>();
+            (id, states)
+        })
+        .filter(|(_, states)| !states.is_empty())
+        .collect()
+}
+
+/// Residency in each state over `elapsed_us`. The states must be the ones
+/// `before` was read from; anything else reads as no residency.
+fn residencies(now: &[IdleState], before: &[IdleState], elapsed_us: f64) -> Vec<IdleResidency> {
+    now.iter()
+        .map(|state| {
+            let spent = before
+                .iter()
+                .find(|previous| previous.name == state.name)
+                .map_or(0, |previous| state.time.saturating_sub(previous.time));
+            IdleResidency {
+                name: state.name.clone(),
+                latency: state.latency,
+                // Time is only accounted when a CPU leaves a state, so a
+                // long sleep can land in one refresh and overshoot.
+                residency: ((spent as f64 / elapsed_us) * 100.0).min(100.0) as f32,
+                disabled: state.disabled,
+            }
+        })
+        .collect()
+}
+
+/// Residency between the last two refreshes; empty until there have been
+/// two.
+pub struct CpuidleSampler {
+    root: PathBuf,
+    previous: Option<(Instant, HashMap<u32, Vec<IdleState>>)>,
+    current: HashMap<u32, Vec<IdleResidency>>,
+}
+
+impl CpuidleSampler {
+    pub fn new(sysfs_root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: sysfs_root.into(),
+            previous: None,
+            current: HashMap::new(),
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        if !cfg!(target_os = "linux") {
+            return;
+        }
+        let states = read_states(&self.root);
+        let now = Instant::now();
+        if let Some((then, previous)) = &self.previous {
+            let elapsed_us = now.duration_since(*then).as_secs_f64() * 1e6;
+            if elapsed_us > 0.0 {
+                self.current = states
+                    .iter()
+                    .filter_map(|(id, now)| {
+                        Some((*id, residencies(now, previous.get(id)?, elapsed_us)))
+                    })
+                    .collect();
+            }
+        }
+        self.previous = Some((now, states));
+    }
+
+    /// One CPU's states, by its sysinfo name (`cpu3`).
+    pub fn core(&self, name: &str) -> Vec<IdleResidency> {
+        topology::cpu_id(name)
+            .and_then(|id| self.current.get(&id))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::Fixture;
+
+    #[test]
+    fn computes_residency_between_reads() {
+        let fixture = Fixture::new("cpuidle");
+        let root = fixture.root();
+        let write_states = |times: [&str; 3]| {
+            for (index, (name, latency, time)) in [
+                ("POLL", "0", times[0]),
+                ("C1", "2", times[1]),
+                ("C6", "170", times[2]),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                let state = format!("devices/system/cpu/cpu0/cpuidle/state{index}");
+                let state = Path::new(&state);
+                fixture.write(state.join("name"), name);
+                fixture.write(state.join("latency"), latency);
+                fixture.write(state.join("time"), time);
+                fixture.write(
+                    state.join("disable"),
+                    if name == "POLL" { "1" } else { "0" },
+                );
+            }
+        };
+        write_states(["100", "2000", "50000"]);
+        fixture.create_dir("devices/system/cpu/cpu1");
+        let before = read_states(root);
+        write_states(["100", "202000", "550000"]);
+        let now = read_states(root);
+
+        assert_eq!(now.len(), 1, "cpu1 has no cpuidle");
+        let residency = residencies(&now[&0], &before[&0], 1_000_000.0);
+        let summary: Vec<(&str, f32, bool)> = residency
+            .iter()
+            .map(|state| (state.name.as_str(), state.residency, state.disabled))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("POLL", 0.0, true),
+                ("C1", 20.0, false),
+                ("C6", 50.0, false)
+            ]
+        );
+        assert_eq!(residency[2].latency, 170);
+    }
+}
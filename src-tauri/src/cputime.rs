@@ -0,0 +1,209 @@
+//! Where CPU time goes: the user/system/iowait/... split from `/proc/stat`.
+//!
+//! sysinfo only reports a single busy percentage per core. The kernel's
+//! counters are cumulative jiffies, so [`CpuTimeSampler`] keeps the previous
+//! reading and reports each refresh as shares of the time since the last
+//! one. Linux only; elsewhere every breakdown is `None`.
+
+use std::{collections::HashMap, fs};
+
+use serde::{Deserialize, Serialize};
+
+/// Percent of the sampled interval spent in each state; the fields sum to
+/// roughly 100.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuTimes {
+    /// Excluding guest time, which the kernel also counts as user time.
+    pub user: f32,
+    /// Excluding niced guest time.
+    pub nice: f32,
+    pub system: f32,
+    pub idle: f32,
+    pub iowait: f32,
+    pub irq: f32,
+    pub softirq: f32,
+    /// Time a hypervisor ran something else while this vCPU wanted to run.
+    pub steal: f32,
+    /// Running a guest's vCPU, niced or not.
+    pub guest: f32,
+}
+
+/// One `cpu` line of `/proc/stat`, in jiffies since boot.
+#[derive(Debug, Clone, Copy, Default)]
+struct Jiffies {
+    user: u64,
+    nice: u64,
+    system: u64,
+    idle: u64,
+    iowait: u64,
+    irq: u64,
+    softirq: u64,
+    steal: u64,
+    guest: u64,
+    guest_nice: u64,
+}
+
+impl Jiffies {
+    fn parse(fields: &str) -> Option<Self> {
+        // Older kernels have fewer columns; missing ones read as zero.
+        let mut values = fields.split_whitespace().map(str::parse::<u64>);
+        let mut next = || values.next().unwrap_or(Ok(0));
+        Some(Self {
+            user: next().ok()?,
+            nice: next().ok()?,
+            system: next().ok()?,
+            idle: next().ok()?,
+            iowait: next().ok()?,
+            irq: next().ok()?,
+            softirq: next().ok()?,
+            steal: next().ok()?,
+            guest: next().ok()?,
+            guest_nice: next().ok()?,
+        })
+    }
+
+    /// Shares of the time between `previous` and `self`, or `None` if no
+    /// time passed (or the counters went backwards, e.g. a CPU was
+    /// hot-unplugged and came back).
+    fn since(&self, previous: &Self) -> Option<CpuTimes> {
+        let delta = |now: u64, then: u64| now.checked_sub(then);
+        let guest = delta(self.guest, previous.guest)?;
+        let guest_nice = delta(self.guest_nice, previous.guest_nice)?;
+        let user = delta(self.user, previous.user)?.saturating_sub(guest);
+        let nice = delta(self.nice, previous.nice)?.saturating_sub(guest_nice);
+        let system = delta(self.system, previous.system)?;
+        let idle = delta(self.idle, previous.idle)?;
+        let iowait = delta(self.iowait, previous.iowait)?;
+        let irq = delta(self.irq, previous.irq)?;
+        let softirq = delta(self.softirq, previous.softirq)?;
+        let steal = delta(self.steal, previous.steal)?;
+        let guest = guest + guest_nice;
+
+        let total = user + nice + system + idle + iowait + irq + softirq + steal + guest;
+        if total == 0 {
+            return None;
+        }
+        let share = |jiffies: u64| (jiffies as f64 * 100.0 / total as f64) as f32;
+        Some(CpuTimes {
+            user: share(user),
+            nice: share(nice),
+            system: share(system),
+            idle: share(idle),
+            iowait: share(iowait),
+            irq: share(irq),
+            softirq: share(softirq),
+            steal: share(steal),
+            guest: share(guest),
+        })
+    }
+}
+
+/// The `cpu` (aggregate) and `cpuN` lines of a `/proc/stat`, by name.
+fn parse_stat(stat: &str) -> HashMap<String, Jiffies> {
+    stat.lines()
+        .filter(|line| line.starts_with("cpu"))
+        .filter_map(|line| {
+            let (name, fields) = line.split_once(char::is_whitespace)?;
+            Some((name.to_string(), Jiffies::parse(fields)?))
+        })
+        .collect()
+}
+
+/// Turns successive `/proc/stat` readings into [`CpuTimes`].
+#[derive(Default)]
+pub struct CpuTimeSampler {
+    previous: HashMap<String, Jiffies>,
+    current: HashMap<String, CpuTimes>,
+}
+
+impl CpuTimeSampler {
+    pub fn refresh(&mut self) {
+        if !cfg!(target_os = "linux") {
+            return;
+        }
+        // A failed read leaves the last breakdown in place rather than
+        // flickering the UI back to "unavailable".
+        if let Ok(stat) = fs::read_to_string("/proc/stat") {
+            self.update(parse_stat(&stat));
+        }
+    }
+
+    fn update(&mut self, readings: HashMap<String, Jiffies>) {
+        self.current = readings
+            .iter()
+            .filter_map(|(name, now)| {
+                let times = now.since(self.previous.get(name)?)?;
+                Some((name.clone(), times))
+            })
+            .collect();
+        self.previous = readings;
+    }
+
+    /// All CPUs together.
+    pub fn global(&self) -> Option<CpuTimes> {
+        self.current.get("cpu").copied()
+    }
+
+    /// One logical CPU, by its `/proc/stat` name (`cpu0`, `cpu1`, ...),
+    /// which is also what sysinfo names CPUs on Linux.
+    pub fn core(&self, name: &str) -> Option<CpuTimes> {
+        self.current.get(name).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BEFORE: &str = "\
+cpu  1000 100 500 8000 200 10 40 50 0 0
+cpu0 600 100 200 4000 100 10 20 0 0 0
+cpu1 400 0 300 4000 100 0 20 50 0 0
+intr 12345 0 0
+ctxt 999
+";
+
+    const AFTER: &str = "\
+cpu  1500 100 700 8500 300 10 40 150 200 0
+cpu0 1000 100 300 4000 100 10 20 0 100 0
+cpu1 400 0 400 4500 200 0 20 150 0 0
+intr 12400 0 0
+ctxt 1200
+";
+
+    #[test]
+    fn breakdown_covers_the_interval_between_readings() {
+        let mut sampler = CpuTimeSampler::default();
+        sampler.update(parse_stat(BEFORE));
+        assert_eq!(sampler.global(), None, "one reading has no interval");
+
+        sampler.update(parse_stat(AFTER));
+        // cpu0: 400 user jiffies of which 100 were a guest, 100 system.
+        assert_eq!(
+            sampler.core("cpu0"),
+            Some(CpuTimes {
+                user: 60.0,
+                system: 20.0,
+                guest: 20.0,
+                ..CpuTimes::default()
+            })
+        );
+        // cpu1: a noisy neighbour.
+        let cpu1 = sampler.core("cpu1").expect("cpu1 has two readings");
+        assert_eq!(cpu1.steal, 12.5);
+        assert_eq!(cpu1.idle, 62.5);
+        assert!(sampler.core("intr").is_none());
+
+        let global = sampler.global().expect("aggregate line is parsed");
+        let sum = global.user
+            + global.nice
+            + global.system
+            + global.idle
+            + global.iowait
+            + global.irq
+            + global.softirq
+            + global.steal
+            + global.guest;
+        assert!((sum - 100.0).abs() < 0.01, "shares sum to {sum}");
+    }
+}
@@ -0,0 +1,164 @@
+//! Per-stream health counters for debugging leaked or stalled streams.
+//!
+//! The collector reports every subscription start and stop and every
+//! snapshot it sends; [`get_stream_status`] reads the counters back. Only
+//! aggregate numbers per [`StreamName`] are kept, so this costs a lock and
+//! a few stores per snapshot, plus serialising one snapshot in
+//! [`PAYLOAD_SAMPLE_EVERY`] a second time to measure it.
+
+use std::{
+    collections::HashMap,
+    io,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::{history::unix_millis, streams::StreamName};
+
+/// How often (in snapshots per subscriber) the payload size is measured.
+/// Sizes drift slowly, and measuring means serialising the snapshot again.
+pub const PAYLOAD_SAMPLE_EVERY: u64 = 16;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StreamStatus {
+    stream: StreamName,
+    /// At least one subscriber is being served.
+    running: bool,
+    subscribers: usize,
+    /// Unix ms when the stream last went from no subscribers to some.
+    started_at: Option<u64>,
+    /// Snapshots sent since `started_at`, across all subscribers.
+    ticks: u64,
+    /// Unix ms of the last snapshot sent.
+    last_tick_at: Option<u64>,
+    /// How long the shared refresh behind the last snapshot took.
+    last_refresh_ms: Option<f64>,
+    /// Building, serialising and sending the last snapshot.
+    last_send_ms: Option<f64>,
+    /// JSON size of the last measured snapshot; see
+    /// [`PAYLOAD_SAMPLE_EVERY`].
+    last_payload_bytes: Option<usize>,
+    /// Last failure to build or send a snapshot; kept across restarts.
+    last_error: Option<String>,
+}
+
+impl StreamStatus {
+    fn new(stream: StreamName) -> Self {
+        Self {
+            stream,
+            running: false,
+            subscribers: 0,
+            started_at: None,
+            ticks: 0,
+            last_tick_at: None,
+            last_refresh_ms: None,
+            last_send_ms: None,
+            last_payload_bytes: None,
+            last_error: None,
+        }
+    }
+}
+
+/// Shared between the collector (writer) and [`get_stream_status`].
+#[derive(Clone, Default)]
+pub struct Diagnostics(Arc<Mutex<HashMap<StreamName, StreamStatus>>>);
+
+impl Diagnostics {
+    pub fn started(&self, stream: StreamName) {
+        self.update(stream, |status| {
+            if status.subscribers == 0 {
+                status.started_at = Some(unix_millis());
+                status.ticks = 0;
+            }
+            status.subscribers += 1;
+            status.running = true;
+        });
+    }
+
+    pub fn stopped(&self, stream: StreamName) {
+        self.update(stream, |status| {
+            status.subscribers = status.subscribers.saturating_sub(1);
+            status.running = status.subscribers > 0;
+        });
+    }
+
+    /// `bytes` is `None` for snapshots whose size wasn't measured.
+    pub fn served(
+        &self,
+        stream: StreamName,
+        refresh: Duration,
+        send: Duration,
+        bytes: Option<usize>,
+    ) {
+        self.update(stream, |status| {
+            status.ticks += 1;
+            status.last_tick_at = Some(unix_millis());
+            status.last_refresh_ms = Some(refresh.as_secs_f64() * 1000.0);
+            status.last_send_ms = Some(send.as_secs_f64() * 1000.0);
+            if bytes.is_some() {
+                status.last_payload_bytes = bytes;
+            }
+        });
+    }
+
+    pub fn failed(&self, stream: StreamName, error: String) {
+        self.update(stream, |status| status.last_error = Some(error));
+    }
+
+    /// One entry per stream name, including ones that never ran.
+    pub fn statuses(&self) -> Vec<StreamStatus> {
+        let statuses = self.lock();
+        StreamName::ALL
+            .into_iter()
+            .map(|stream| {
+                statuses
+                    .get(&stream)
+                    .cloned()
+                    .unwrap_or_else(|| StreamStatus::new(stream))
+            })
+            .collect()
+    }
+
+    fn update(&self, stream: StreamName, apply: impl FnOnce(&mut StreamStatus)) {
+        apply(
+            self.lock()
+                .entry(stream)
+                .or_insert_with(|| StreamStatus::new(stream)),
+        );
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<StreamName, StreamStatus>> {
+        // Counters only; whatever a panicked writer left is still useful.
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Size of `value` as the JSON Tauri sends over IPC, without allocating it.
+pub fn payload_size(value: &impl Serialize) -> serde_json::Result<usize> {
+    let mut counter = ByteCounter(0);
+    serde_json::to_writer(&mut counter, value)?;
+    Ok(counter.0)
+}
+
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[tauri::command]
+pub fn get_stream_status(diagnostics: State<'_, Diagnostics>) -> Vec<StreamStatus> {
+    diagnostics.statuses()
+}
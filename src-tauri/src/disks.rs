@@ -1,10 +1,11 @@
-use std::sync::atomic::Ordering;
-
 use serde::Serialize;
-use sysinfo::{DiskKind, Disks};
-use tauri::{ipc::Channel, State};
+use tauri::{ipc::Channel, State, Window};
 
-use crate::streams::{StreamName, StreamRegistry, SAMPLE_INTERVAL};
+use crate::{
+    hub::{Hub, Tick},
+    source::MetricsSource,
+    streams::{StreamName, StreamRegistry, SubscriberId},
+};
 
 #[derive(Debug, Serialize, Clone)]
 pub struct DiskInfo {
@@ -15,48 +16,50 @@ pub struct DiskInfo {
     total_space: u64,
     available_space: u64,
     removable: bool,
-    /// Bytes read since the previous 1-second refresh, i.e. ~bytes/sec.
+    /// Bytes per second since the previous refresh.
     read_rate: u64,
     write_rate: u64,
 }
 
-#[tauri::command]
-pub async fn get_disks_info(
-    registry: State<'_, StreamRegistry>,
-    on_event: Channel<Vec<DiskInfo>>,
-) -> Result<(), String> {
-    let cancelled = registry.begin(StreamName::Disks);
-    let mut disks = Disks::new_with_refreshed_list();
-
-    while !cancelled.load(Ordering::Relaxed) {
-        disks.refresh(true);
+#[derive(Debug, Serialize, Clone)]
+pub struct DisksSnapshot {
+    #[serde(flatten)]
+    tick: Tick,
+    disks: Vec<DiskInfo>,
+}
 
-        let snapshot: Vec<DiskInfo> = disks
-            .iter()
-            .map(|disk| {
-                let usage = disk.usage();
-                DiskInfo {
-                    name: disk.name().to_string_lossy().into_owned(),
-                    mount_point: disk.mount_point().to_string_lossy().into_owned(),
-                    file_system: disk.file_system().to_string_lossy().into_owned(),
-                    kind: match disk.kind() {
-                        DiskKind::HDD => "HDD".to_string(),
-                        DiskKind::SSD => "SSD".to_string(),
-                        DiskKind::Unknown(_) => "Unknown".to_string(),
-                    },
-                    total_space: disk.total_space(),
-                    available_space: disk.available_space(),
-                    removable: disk.is_removable(),
-                    read_rate: usage.read_bytes,
-                    write_rate: usage.written_bytes,
-                }
-            })
-            .collect();
+fn snapshot(source: &dyn MetricsSource, tick: Tick) -> DisksSnapshot {
+    let disks = source
+        .disks()
+        .into_iter()
+        .map(|disk| DiskInfo {
+            name: disk.name,
+            mount_point: disk.mount_point,
+            file_system: disk.file_system,
+            kind: disk.kind,
+            total_space: disk.total_space,
+            available_space: disk.available_space,
+            removable: disk.removable,
+            read_rate: disk.read_rate,
+            write_rate: disk.write_rate,
+        })
+        .collect();
+    DisksSnapshot { tick, disks }
+}
 
-        if on_event.send(snapshot).is_err() {
-            break; // Webview is gone; nobody is listening anymore.
-        }
-        tokio::time::sleep(SAMPLE_INTERVAL).await;
-    }
-    Ok(())
+#[tauri::command]
+pub fn get_disks_info(
+    registry: State<'_, StreamRegistry>,
+    hub: State<'_, Hub>,
+    window: Window,
+    on_event: Channel<DisksSnapshot>,
+    token: String,
+    interval_ms: Option<u64>,
+) {
+    let stream = registry.begin(
+        StreamName::Disks,
+        SubscriberId::new(&window, token),
+        interval_ms,
+    );
+    hub.subscribe(stream, on_event, snapshot);
 }
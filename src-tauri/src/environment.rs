@@ -0,0 +1,307 @@
+//! Whether we run on bare metal, in a VM or in a container, and the cgroup
+//! limits that come with a container.
+//!
+//! sysinfo reports what the kernel sees: in a VM that is vCPUs the host may
+//! be overcommitting, and in a container it is the host's CPUs and memory
+//! rather than the share we may actually use. Container and cgroup
+//! detection is Linux only; the hypervisor comes from `cpuid` anywhere on
+//! x86 and from DMI strings on Linux.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::{cpuid, sysfs};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Environment {
+    /// e.g. "KVM", "VMware", "Hyper-V"; `None` on bare metal.
+    pub hypervisor: Option<String>,
+    /// e.g. "docker", "podman", "lxc", "kubernetes"; `None` outside one.
+    pub container: Option<String>,
+    pub limits: CgroupLimits,
+}
+
+/// The tightest limits on our cgroup and its ancestors; `None` where
+/// nothing is set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct CgroupLimits {
+    /// CPUs' worth of time per period, e.g. 1.5.
+    pub cpu_quota: Option<f64>,
+    /// Bytes.
+    pub memory_limit: Option<u64>,
+}
+
+/// Recognises the `sys_vendor` and `product_name` a VM's firmware reports.
+fn dmi_hypervisor(vendor: &str, product: &str) -> Option<&'static str> {
+    Some(match (vendor, product) {
+        (_, "KVM") => "KVM",
+        ("QEMU", _) => "QEMU",
+        ("VMware, Inc.", _) => "VMware",
+        ("innotek GmbH", _) | (_, "VirtualBox") => "VirtualBox",
+        ("Xen", _) => "Xen",
+        ("Parallels Software International Inc.", _) => "Parallels",
+        ("BHYVE", _) => "bhyve",
+        ("Microsoft Corporation", "Virtual Machine") => "Hyper-V",
+        ("Google", "Google Compute Engine") => "Google Compute Engine",
+        // Bare-metal instances share the vendor string.
+        ("Amazon EC2", product) if !product.ends_with(".metal") => "Amazon EC2",
+        _ => return None,
+    })
+}
+
+fn hypervisor(sysfs_root: &Path) -> Option<String> {
+    if let Some(hypervisor) = cpuid::hypervisor() {
+        return Some(hypervisor);
+    }
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let dmi = sysfs_root.join("class/dmi/id");
+    let vendor = sysfs::read_string(&dmi.join("sys_vendor"))?;
+    let product = sysfs::read_string(&dmi.join("product_name")).unwrap_or_default();
+    dmi_hypervisor(&vendor, &product).map(str::to_string)
+}
+
+/// Checks, in order of how specific they are, the `container` variable
+/// most runtimes give PID 1, the marker files Podman and Docker create,
+/// and the cgroup PID 1 was started in, all relative to `root`.
+fn container(root: &Path) -> Option<String> {
+    let read = |path: &str| fs::read(root.join(path)).ok();
+    if let Some(environ) = read("proc/1/environ") {
+        let variables = String::from_utf8_lossy(&environ);
+        if let Some(name) = variables
+            .split('\0')
+            .find_map(|variable| variable.strip_prefix("container="))
+        {
+            return Some(name.to_string());
+        }
+    }
+    if root.join("run/.containerenv").exists() {
+        return Some("podman".to_string());
+    }
+    if root.join(".dockerenv").exists() {
+        return Some("docker".to_string());
+    }
+    // Only tells us anything without a cgroup namespace, which hides the
+    // path as "/".
+    let cgroup = String::from_utf8(read("proc/1/cgroup")?).ok()?;
+    let path = cgroup.lines().filter_map(|line| line.splitn(3, ':').nth(2));
+    [
+        ("kubepods", "kubernetes"),
+        ("libpod", "podman"),
+        ("docker", "docker"),
+        ("lxc", "lxc"),
+    ]
+    .into_iter()
+    .find(|(marker, _)| path.clone().any(|path| path.contains(marker)))
+    .map(|(_, name)| name.to_string())
+}
+
+/// The tightest limit `read` finds in `path` or any of its ancestors up to
+/// the hierarchy's `mount`. Under cgroup v1 the path we're given may
+/// belong to the host and not exist in our mount namespace; then only the
+/// mount itself, which is our own cgroup, is read.
+fn tightest<T: PartialOrd>(
+    mount: &Path,
+    path: &str,
+    read: impl Fn(&Path) -> Option<T>,
+) -> Option<T> {
+    let mut dir = mount.to_path_buf();
+    let mut tightest = read(&dir);
+    for component in path.split('/').filter(|component| !component.is_empty()) {
+        dir.push(component);
+        if let Some(limit) = read(&dir) {
+            if tightest.as_ref().is_none_or(|tightest| limit < *tightest) {
+                tightest = Some(limit);
+            }
+        }
+    }
+    tightest
+}
+
+/// `cpu.max` is "max 100000" or "<quota> <period>".
+fn read_cpu_max(dir: &Path) -> Option<f64> {
+    let max = sysfs::read_string(&dir.join("cpu.max"))?;
+    let (quota, period) = max.split_once(' ')?;
+    Some(quota.parse::<f64>().ok()? / period.parse::<f64>().ok()?)
+}
+
+fn read_cfs_quota(dir: &Path) -> Option<f64> {
+    let quota = sysfs::read_i64(&dir.join("cpu.cfs_quota_us")).filter(|&quota| quota > 0)?;
+    let period = sysfs::read_i64(&dir.join("cpu.cfs_period_us")).filter(|&period| period > 0)?;
+    Some(quota as f64 / period as f64)
+}
+
+/// `memory.max` is "max" when unlimited, which fails to parse.
+fn read_memory_max(dir: &Path) -> Option<u64> {
+    sysfs::read_string(&dir.join("memory.max"))?.parse().ok()
+}
+
+/// v1 reports "unlimited" as the largest page-aligned `i64`.
+fn read_memory_limit_in_bytes(dir: &Path) -> Option<u64> {
+    sysfs::read_i64(&dir.join("memory.limit_in_bytes"))
+        .filter(|&limit| limit < 1 << 62)
+        .and_then(|limit| u64::try_from(limit).ok())
+}
+
+/// Limits for the cgroups listed in `self_cgroup` (`/proc/self/cgroup`),
+/// with the hierarchies mounted under `cgroup_root`.
+fn cgroup_limits(cgroup_root: &Path, self_cgroup: &str) -> CgroupLimits {
+    let mut limits = CgroupLimits::default();
+    for line in self_cgroup.lines() {
+        let mut fields = line.splitn(3, ':');
+        let (Some(_), Some(controllers), Some(path)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        if controllers.is_empty() {
+            // The unified (v2) hierarchy.
+            limits.cpu_quota = limits
+                .cpu_quota
+                .or_else(|| tightest(cgroup_root, path, read_cpu_max));
+            limits.memory_limit = limits
+                .memory_limit
+                .or_else(|| tightest(cgroup_root, path, read_memory_max));
+            continue;
+        }
+        let mount: PathBuf = cgroup_root.join(controllers);
+        let controllers: Vec<&str> = controllers.split(',').collect();
+        if controllers.contains(&"cpu") {
+            limits.cpu_quota = limits
+                .cpu_quota
+                .or_else(|| tightest(&mount, path, read_cfs_quota));
+        }
+        if controllers.contains(&"memory") {
+            limits.memory_limit = limits
+                .memory_limit
+                .or_else(|| tightest(&mount, path, read_memory_limit_in_bytes));
+        }
+    }
+    limits
+}
+
+/// Reads procfs, sysfs and the container marker files under `root`, which
+/// is `/` outside tests.
+pub fn environment(root: &Path) -> Environment {
+    // Set in every pod, whatever the runtime underneath.
+    let in_pod = env::var_os("KUBERNETES_SERVICE_HOST").is_some();
+    read_environment(root, in_pod)
+}
+
+fn read_environment(root: &Path, in_pod: bool) -> Environment {
+    let sysfs_root = root.join("sys");
+    let hypervisor = hypervisor(&sysfs_root);
+    if !cfg!(target_os = "linux") {
+        return Environment {
+            hypervisor,
+            ..Environment::default()
+        };
+    }
+    let container = if in_pod {
+        Some("kubernetes".to_string())
+    } else {
+        container(root)
+    };
+    let limits = fs::read_to_string(root.join("proc/self/cgroup"))
+        .map(|self_cgroup| cgroup_limits(&sysfs_root.join("fs/cgroup"), &self_cgroup))
+        .unwrap_or_default();
+    Environment {
+        hypervisor,
+        container,
+        limits,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::Fixture;
+
+    #[test]
+    fn detects_containers() {
+        let fixture = Fixture::new("environment-containers");
+        let root = |name: &str| fixture.root().join(name);
+
+        fixture.write("host/proc/1/cgroup", "0::/init.scope");
+        assert_eq!(container(&root("host")), None);
+        fixture.write("docker/.dockerenv", "");
+        assert_eq!(container(&root("docker")).as_deref(), Some("docker"));
+        fixture.write("lxc/.dockerenv", "");
+        fixture.write("lxc/proc/1/environ", "HOME=/\0container=lxc\0");
+        assert_eq!(container(&root("lxc")).as_deref(), Some("lxc"));
+        fixture.write("pod/proc/1/cgroup", "0::/kubepods/burstable/pod1/abc");
+        assert_eq!(container(&root("pod")).as_deref(), Some("kubernetes"));
+    }
+
+    #[test]
+    fn takes_the_tightest_cgroup_v2_limits() {
+        let fixture = Fixture::new("environment-v2");
+        // The parent caps memory tighter than the leaf, and only the leaf
+        // limits CPU.
+        fixture.write("system.slice/memory.max", "1073741824");
+        fixture.write("system.slice/cpu.max", "max 100000");
+        fixture.write("system.slice/app.service/memory.max", "2147483648");
+        fixture.write("system.slice/app.service/cpu.max", "150000 100000");
+        assert_eq!(
+            cgroup_limits(fixture.root(), "0::/system.slice/app.service\n"),
+            CgroupLimits {
+                cpu_quota: Some(1.5),
+                memory_limit: Some(1 << 30),
+            }
+        );
+        assert_eq!(
+            cgroup_limits(fixture.root(), "0::/user.slice\n"),
+            CgroupLimits::default()
+        );
+    }
+
+    #[test]
+    fn reads_cgroup_v1_limits_from_the_mount_root() {
+        // Inside a container the host path doesn't exist; the mount root
+        // is ours.
+        let fixture = Fixture::new("environment-v1");
+        fixture.write("cpu,cpuacct/cpu.cfs_quota_us", "200000");
+        fixture.write("cpu,cpuacct/cpu.cfs_period_us", "100000");
+        fixture.write("memory/memory.limit_in_bytes", "9223372036854771712");
+        assert_eq!(
+            cgroup_limits(
+                fixture.root(),
+                "4:memory:/docker/abc\n3:cpu,cpuacct:/docker/abc\n1:name=systemd:/docker/abc\n"
+            ),
+            CgroupLimits {
+                cpu_quota: Some(2.0),
+                memory_limit: None,
+            }
+        );
+    }
+
+    // Elsewhere only the hypervisor is looked up.
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reads_everything_under_the_given_root() {
+        let fixture = Fixture::new("environment-root");
+        fixture.write(".dockerenv", "");
+        fixture.write("proc/self/cgroup", "0::/");
+        fixture.write("sys/fs/cgroup/memory.max", "536870912");
+
+        let environment = read_environment(fixture.root(), false);
+        assert_eq!(environment.container.as_deref(), Some("docker"));
+        assert_eq!(environment.limits.memory_limit, Some(512 << 20));
+        let in_pod = read_environment(fixture.root(), true);
+        assert_eq!(in_pod.container.as_deref(), Some("kubernetes"));
+    }
+
+    #[test]
+    fn recognises_hypervisor_dmi_strings() {
+        assert_eq!(
+            dmi_hypervisor("QEMU", "Standard PC (Q35 + ICH9, 2009)"),
+            Some("QEMU")
+        );
+        assert_eq!(dmi_hypervisor("Amazon EC2", "m5.metal"), None);
+    }
+}
@@ -0,0 +1,114 @@
+//! The error type every fallible command returns.
+//!
+//! It serialises as `{ kind, message, ...details }` so the frontend can
+//! branch on `kind` (e.g. only suggest elevated privileges on
+//! `permission_denied`) and still show `message` for anything it doesn't
+//! handle specially.
+
+use std::{fmt, io, path::PathBuf};
+
+use serde::{ser::SerializeMap, Serialize, Serializer};
+
+use crate::streams::StreamName;
+
+#[derive(Debug)]
+pub enum Error {
+    /// `what` names the missing thing, e.g. "Process 1234".
+    NotFound {
+        what: String,
+    },
+    /// `action` is what was attempted, e.g. "signal process 1234".
+    PermissionDenied {
+        action: String,
+    },
+    /// Monsoon refuses to touch this process at all.
+    ProtectedProcess {
+        pid: u32,
+        reason: &'static str,
+    },
+    Unsupported {
+        feature: String,
+    },
+    /// The stream isn't running (any more) for this subscriber.
+    StreamCancelled {
+        stream: StreamName,
+    },
+    Io {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+}
+
+impl Error {
+    /// An I/O error about a specific file or directory.
+    pub fn io_at(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        Self::Io {
+            path: Some(path.into()),
+            source,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound { .. } => "not_found",
+            Self::PermissionDenied { .. } => "permission_denied",
+            Self::ProtectedProcess { .. } => "protected_process",
+            Self::Unsupported { .. } => "unsupported",
+            Self::StreamCancelled { .. } => "stream_cancelled",
+            Self::Io { .. } => "io",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound { what } => write!(f, "{what} not found"),
+            Self::PermissionDenied { action } => write!(f, "Not permitted to {action}"),
+            Self::ProtectedProcess { reason, .. } => write!(f, "Refusing to terminate {reason}"),
+            Self::Unsupported { feature } => {
+                write!(f, "{feature} is not supported on this platform")
+            }
+            Self::StreamCancelled { stream } => write!(f, "The {stream:?} stream is not running"),
+            Self::Io {
+                path: Some(path),
+                source,
+            } => write!(f, "{}: {source}", path.display()),
+            Self::Io { path: None, source } => source.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(source: io::Error) -> Self {
+        Self::Io { path: None, source }
+    }
+}
+
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            Self::NotFound { what } => map.serialize_entry("what", what)?,
+            Self::PermissionDenied { action } => map.serialize_entry("action", action)?,
+            Self::ProtectedProcess { pid, .. } => map.serialize_entry("pid", pid)?,
+            Self::Unsupported { feature } => map.serialize_entry("feature", feature)?,
+            Self::StreamCancelled { stream } => map.serialize_entry("stream", stream)?,
+            Self::Io { path, .. } => map.serialize_entry("path", path)?,
+        }
+        map.end()
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
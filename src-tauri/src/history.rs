@@ -0,0 +1,371 @@
+//! Bounded in-memory history so charts can backfill on open.
+//!
+//! The collector records one point per metric every
+//! [`SAMPLE_INTERVAL`](crate::streams::SAMPLE_INTERVAL),
+//! whether or not any page is subscribed, and keeps the last
+//! [`HISTORY_CAPACITY`] points per series. While every window is hidden it
+//! records every [`HIDDEN_SAMPLE_INTERVAL`](crate::streams::HIDDEN_SAMPLE_INTERVAL)
+//! instead, so the same points then reach further back.
+
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::{
+    error::{Error, Result},
+    source::MetricsSource,
+};
+
+/// Five minutes at one point per second.
+pub const HISTORY_CAPACITY: usize = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    /// Global CPU usage, percent.
+    Cpu,
+    /// Per-core CPU usage, percent; one series per core name.
+    CpuCores,
+    /// Used RAM, percent of total.
+    Memory,
+    /// Used swap, percent of total.
+    Swap,
+    /// Bytes received per second; one series per interface.
+    NetworkRx,
+    NetworkTx,
+    /// Bytes read per second; one series per mount point.
+    DiskRead,
+    DiskWrite,
+    /// CPU usage of the busiest processes, percent of one core; one series
+    /// per process name. Only kept by the [metric store](crate::store), so
+    /// [`get_history`] rejects it.
+    ProcessCpu,
+    /// Resident memory of the same processes, bytes.
+    ProcessMemory,
+}
+
+impl Metric {
+    /// Every metric, indexed by its on-disk code in [`crate::store`].
+    pub const ALL: [Self; 10] = [
+        Self::Cpu,
+        Self::CpuCores,
+        Self::Memory,
+        Self::Swap,
+        Self::NetworkRx,
+        Self::NetworkTx,
+        Self::DiskRead,
+        Self::DiskWrite,
+        Self::ProcessCpu,
+        Self::ProcessMemory,
+    ];
+
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        Self::ALL.get(usize::from(code)).copied()
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy)]
+pub struct HistoryPoint {
+    /// Unix time in milliseconds.
+    pub timestamp: u64,
+    pub value: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HistorySeries {
+    pub name: String,
+    /// Oldest first.
+    pub points: Vec<HistoryPoint>,
+}
+
+type Series = BTreeMap<String, VecDeque<HistoryPoint>>;
+
+#[derive(Clone, Default)]
+pub struct History(Arc<Mutex<HashMap<Metric, Series>>>);
+
+/// One value of one series at one instant.
+pub struct Sample {
+    pub metric: Metric,
+    pub name: String,
+    pub value: f64,
+}
+
+/// Reads every history metric out of a freshly refreshed source.
+pub fn sample(source: &impl MetricsSource) -> Vec<Sample> {
+    let mut samples = Vec::new();
+    let mut push = |metric: Metric, name: &str, value: f64| {
+        samples.push(Sample {
+            metric,
+            name: name.to_string(),
+            value,
+        });
+    };
+
+    push(Metric::Cpu, "cpu", f64::from(source.global_cpu_usage()));
+    for cpu in source.cpus() {
+        push(Metric::CpuCores, &cpu.name, f64::from(cpu.usage));
+    }
+    let memory = source.memory();
+    push(Metric::Memory, "memory", percent(memory.used, memory.total));
+    push(
+        Metric::Swap,
+        "swap",
+        percent(memory.swap_used, memory.swap_total),
+    );
+
+    for network in source.networks() {
+        push(
+            Metric::NetworkRx,
+            &network.name,
+            network.received_rate as f64,
+        );
+        push(
+            Metric::NetworkTx,
+            &network.name,
+            network.transmitted_rate as f64,
+        );
+    }
+    for disk in source.disks() {
+        push(Metric::DiskRead, &disk.mount_point, disk.read_rate as f64);
+        push(Metric::DiskWrite, &disk.mount_point, disk.write_rate as f64);
+    }
+    samples
+}
+
+/// The `n` process names using the most CPU. Processes sharing a name
+/// (worker pools, browser tabs) are added up, which is what "what was
+/// eating the CPU" wants answered.
+pub fn sample_processes(source: &impl MetricsSource, n: usize) -> Vec<Sample> {
+    let mut by_name: HashMap<String, (f64, u64)> = HashMap::new();
+    for process in source.processes() {
+        let (cpu, memory) = by_name.entry(process.name).or_default();
+        *cpu += f64::from(process.cpu_usage);
+        *memory += process.memory;
+    }
+    let mut busiest: Vec<_> = by_name.into_iter().collect();
+    busiest.sort_by(|(_, (a, _)), (_, (b, _))| b.total_cmp(a));
+    busiest
+        .into_iter()
+        .take(n)
+        .flat_map(|(name, (cpu, memory))| {
+            [
+                Sample {
+                    metric: Metric::ProcessCpu,
+                    name: name.clone(),
+                    value: cpu,
+                },
+                Sample {
+                    metric: Metric::ProcessMemory,
+                    name,
+                    value: memory as f64,
+                },
+            ]
+        })
+        .collect()
+}
+
+impl History {
+    pub fn record(&self, timestamp: u64, samples: &[Sample]) {
+        let mut metrics = self.lock();
+        for sample in samples {
+            let points = metrics
+                .entry(sample.metric)
+                .or_default()
+                .entry(sample.name.clone())
+                .or_default();
+            if points.len() == HISTORY_CAPACITY {
+                points.pop_front();
+            }
+            points.push_back(HistoryPoint {
+                timestamp,
+                value: sample.value,
+            });
+        }
+
+        // Drop series for interfaces and disks that have gone away.
+        let horizon = window_start(timestamp, HISTORY_CAPACITY as u64);
+        for series in metrics.values_mut() {
+            series.retain(|_, points| points.back().is_some_and(|p| p.timestamp >= horizon));
+        }
+    }
+
+    /// The last `seconds` of `metric`, one series per name.
+    pub fn query(&self, metric: Metric, seconds: u64) -> Vec<HistorySeries> {
+        let since = window_start(unix_millis(), seconds);
+        self.lock()
+            .get(&metric)
+            .map(|series| {
+                series
+                    .iter()
+                    .map(|(name, points)| HistorySeries {
+                        name: name.clone(),
+                        points: points
+                            .iter()
+                            .filter(|point| point.timestamp >= since)
+                            .copied()
+                            .collect(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Metric, Series>> {
+        // Same reasoning as the stream registry: a panicked writer leaves
+        // the buffers intact, so keep serving them.
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn percent(used: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        used as f64 / total as f64 * 100.0
+    }
+}
+
+pub fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX))
+        .unwrap_or(0)
+}
+
+fn window_start(now: u64, seconds: u64) -> u64 {
+    now.saturating_sub(seconds.saturating_mul(1000))
+}
+
+#[tauri::command]
+pub fn get_history(
+    history: State<'_, History>,
+    metric: Metric,
+    seconds: u64,
+) -> Result<Vec<HistorySeries>> {
+    if let Metric::ProcessCpu | Metric::ProcessMemory = metric {
+        return Err(Error::Unsupported {
+            feature: "In-memory history of process metrics".to_string(),
+        });
+    }
+    Ok(history.query(metric, seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::{
+        scripted::{Frame, ScriptedSource},
+        ProcessReading,
+    };
+
+    fn sample(metric: Metric, name: &str, value: f64) -> Sample {
+        Sample {
+            metric,
+            name: name.to_string(),
+            value,
+        }
+    }
+
+    fn values(series: &HistorySeries) -> Vec<f64> {
+        series.points.iter().map(|point| point.value).collect()
+    }
+
+    #[test]
+    fn keeps_the_newest_points_per_series() {
+        let history = History::default();
+        let now = unix_millis();
+        for second in 0..=HISTORY_CAPACITY as u64 {
+            let timestamp = now - (HISTORY_CAPACITY as u64 - second) * 1000;
+            history.record(timestamp, &[sample(Metric::Cpu, "cpu", second as f64)]);
+        }
+
+        let series = history.query(Metric::Cpu, 3600);
+        assert_eq!(series.len(), 1);
+        let values = values(&series[0]);
+        assert_eq!(values.len(), HISTORY_CAPACITY);
+        assert_eq!(values.first(), Some(&1.0), "the oldest point was evicted");
+        assert_eq!(values.last(), Some(&(HISTORY_CAPACITY as f64)));
+    }
+
+    #[test]
+    fn drops_series_that_stopped_reporting() {
+        let history = History::default();
+        let now = unix_millis();
+        let before = now - (HISTORY_CAPACITY as u64 + 1) * 1000;
+        history.record(
+            before,
+            &[
+                sample(Metric::NetworkRx, "eth0", 10.0),
+                sample(Metric::NetworkRx, "wlan0", 20.0),
+            ],
+        );
+        history.record(now, &[sample(Metric::NetworkRx, "eth0", 30.0)]);
+
+        let series = history.query(Metric::NetworkRx, 3600);
+        let names: Vec<&str> = series.iter().map(|series| series.name.as_str()).collect();
+        assert_eq!(names, ["eth0"]);
+        assert_eq!(values(&series[0]), [10.0, 30.0]);
+    }
+
+    #[test]
+    fn query_returns_only_the_requested_window() {
+        let history = History::default();
+        let now = unix_millis();
+        for (age, value) in [(120, 1.0), (30, 2.0), (0, 3.0)] {
+            history.record(now - age * 1000, &[sample(Metric::Memory, "memory", value)]);
+        }
+
+        assert_eq!(values(&history.query(Metric::Memory, 60)[0]), [2.0, 3.0]);
+        assert_eq!(
+            values(&history.query(Metric::Memory, 300)[0]),
+            [1.0, 2.0, 3.0]
+        );
+        assert!(history.query(Metric::Swap, 300).is_empty());
+    }
+
+    #[test]
+    fn samples_the_busiest_process_names() {
+        let process = |pid, name: &str, cpu_usage, memory| ProcessReading {
+            pid,
+            name: name.to_string(),
+            cpu_usage,
+            memory,
+            ..ProcessReading::default()
+        };
+        let source = ScriptedSource::from(Frame {
+            processes: vec![
+                process(1, "init", 0.5, 10),
+                process(2, "worker", 30.0, 100),
+                process(3, "worker", 30.0, 200),
+                process(4, "compiler", 50.0, 1000),
+            ],
+            ..Frame::default()
+        });
+
+        let samples = sample_processes(&source, 2);
+        let samples: Vec<(Metric, &str, f64)> = samples
+            .iter()
+            .map(|sample| (sample.metric, sample.name.as_str(), sample.value))
+            .collect();
+        assert_eq!(
+            samples,
+            [
+                (Metric::ProcessCpu, "worker", 60.0),
+                (Metric::ProcessMemory, "worker", 300.0),
+                (Metric::ProcessCpu, "compiler", 50.0),
+                (Metric::ProcessMemory, "compiler", 1000.0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,739 @@
+//! Shared sampling hub.
+//!
+//! A single collector thread owns every sysinfo handle and refreshes each
+//! subsystem at most once per tick, and only while some stream needs it.
+//! Streaming commands don't sample anything themselves: they subscribe a
+//! snapshot builder, and the collector runs every due builder against the
+//! freshly refreshed [`Sources`] and sends the result down its channel.
+//!
+//! Ticks sit on a grid anchored at the hub's creation, so streams with the
+//! same (or a multiple of the same) interval are served by one refresh, and
+//! the period doesn't drift by however long the refresh work takes. Every
+//! snapshot carries a [`Tick`] saying when it was taken and whether the
+//! collector fell behind and skipped grid points to get there. Streams of a
+//! hidden window are scheduled on their slower (or paused) hidden interval,
+//! see [`Stream::effective_interval`].
+//! The collector also keeps its own once-a-second tick feeding [`History`],
+//! so charts can backfill even for pages that weren't open. While every
+//! window is hidden, that tick slows to [`HIDDEN_SAMPLE_INTERVAL`]. Every
+//! [`PROCESS_SAMPLE_INTERVAL`] it also refreshes processes so the busiest
+//! can go to the [`Store`]. A running [`Recorder`] gets a frame on every
+//! tick that serves a live stream. While a [`Replay`] is loaded, builders
+//! read from it instead of the live sources.
+
+use std::{
+    path::Path,
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use sysinfo::{
+    Components, CpuRefreshKind, Disks, Networks, ProcessRefreshKind, ProcessesToUpdate, System,
+    UpdateKind, MINIMUM_CPU_UPDATE_INTERVAL,
+};
+use tauri::ipc::{Channel, IpcResponse};
+
+use crate::{
+    cpufreq::CpufreqSampler,
+    cpuidle::CpuidleSampler,
+    cputime::CpuTimeSampler,
+    diagnostics::{self, Diagnostics},
+    history::{self, History},
+    hwmon::Hwmon,
+    interrupts::InterruptSampler,
+    pressure::PressureSampler,
+    session::{Recorder, Replay},
+    source::MetricsSource,
+    store::{self, Store, PROCESS_SAMPLE_INTERVAL},
+    streams::{duration_ms, Stream, StreamName, HIDDEN_SAMPLE_INTERVAL, SAMPLE_INTERVAL},
+    sysfs,
+    topology::CoreClasses,
+};
+
+/// The sysinfo handles behind the app's [`MetricsSource`](crate::source::MetricsSource).
+pub struct Sources {
+    /// CPU and memory.
+    pub system: System,
+    /// Processes only. Kept apart from `system` because sysinfo computes
+    /// per-process CPU usage against the CPU times of the *same* `System`;
+    /// sharing one with a faster CPU stream would inflate every process.
+    pub processes: System,
+    pub networks: Networks,
+    pub disks: Disks,
+    /// Temperatures off Linux. On Linux `hwmon` reads them under the
+    /// configured sysfs root, along with fans and voltages.
+    pub components: Components,
+    pub hwmon: Hwmon,
+    /// Load averages and PSI.
+    pub pressure: PressureSampler,
+    pub interrupts: InterruptSampler,
+    /// The `/proc/stat` breakdown, refreshed alongside `system`'s CPUs.
+    pub cpu_times: CpuTimeSampler,
+    /// Governor and time-in-state, refreshed with the CPU frequencies.
+    pub cpufreq: CpufreqSampler,
+    /// C-state residency, only refreshed for subscribers that ask for it.
+    pub cpuidle: CpuidleSampler,
+    pub core_classes: CoreClasses,
+    networks_window: RateWindow,
+    disks_window: RateWindow,
+}
+
+impl Sources {
+    fn new() -> Self {
+        Self {
+            system: System::new(),
+            processes: System::new(),
+            networks: Networks::new(),
+            disks: Disks::new(),
+            components: Components::new(),
+            hwmon: Hwmon::new(sysfs::ROOT),
+            pressure: PressureSampler::default(),
+            interrupts: InterruptSampler::default(),
+            cpu_times: CpuTimeSampler::default(),
+            cpufreq: CpufreqSampler::new(sysfs::ROOT),
+            cpuidle: CpuidleSampler::new(sysfs::ROOT),
+            core_classes: CoreClasses::load(Path::new(sysfs::ROOT)),
+            networks_window: RateWindow::default(),
+            disks_window: RateWindow::default(),
+        }
+    }
+
+    /// Converts a network byte counter delta into bytes per second.
+    pub fn network_rate(&self, bytes: u64) -> u64 {
+        self.networks_window.per_second(bytes)
+    }
+
+    /// Converts a disk I/O byte counter delta into bytes per second.
+    pub fn disk_rate(&self, bytes: u64) -> u64 {
+        self.disks_window.per_second(bytes)
+    }
+
+    fn refresh(&mut self, demand: Demand) {
+        if demand.cpu {
+            let mut refresh = CpuRefreshKind::nothing().with_cpu_usage();
+            if demand.cpu_frequency {
+                refresh = refresh.with_frequency();
+                self.cpufreq.refresh();
+            }
+            self.system.refresh_cpu_specifics(refresh);
+            self.cpu_times.refresh();
+            if demand.cpu_idle {
+                self.cpuidle.refresh();
+            }
+        }
+        if demand.memory {
+            self.system.refresh_memory();
+        }
+        if demand.processes {
+            let mut refresh = ProcessRefreshKind::nothing().with_cpu().with_memory();
+            if demand.process_details {
+                // Only what the UI shows — everything() would also collect
+                // environ, cwd, disk usage, etc. for every process.
+                refresh = refresh
+                    .with_cmd(UpdateKind::OnlyIfNotSet)
+                    .with_exe(UpdateKind::OnlyIfNotSet);
+            }
+            self.processes
+                .refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+        }
+        if demand.networks {
+            self.networks.refresh(true);
+            self.networks_window.mark();
+        }
+        if demand.disks {
+            self.disks.refresh(true);
+            self.disks_window.mark();
+        }
+        if demand.sensors {
+            if !cfg!(target_os = "linux") {
+                self.components.refresh(true);
+            }
+            self.hwmon.refresh();
+        }
+        if demand.load {
+            self.pressure.refresh();
+        }
+        if demand.interrupts {
+            self.interrupts.refresh();
+        }
+    }
+}
+
+/// Time between the last two refreshes of a subsystem whose counters are
+/// deltas. Refreshes are shared between streams with different intervals,
+/// so a delta covers whatever time passed, not any one stream's period.
+#[derive(Default)]
+struct RateWindow {
+    last: Option<Instant>,
+    elapsed: Duration,
+}
+
+impl RateWindow {
+    fn mark(&mut self) {
+        let now = Instant::now();
+        self.elapsed = self
+            .last
+            .map_or(Duration::ZERO, |last| now.duration_since(last));
+        self.last = Some(now);
+    }
+
+    fn per_second(&self, bytes: u64) -> u64 {
+        if self.elapsed.is_zero() {
+            return 0;
+        }
+        (bytes as f64 / self.elapsed.as_secs_f64()).round() as u64
+    }
+}
+
+/// When a snapshot was taken, flattened into every streamed payload.
+#[derive(Debug, Serialize, Clone, Copy, Default)]
+pub struct Tick {
+    /// Unix time in milliseconds of the refresh behind the snapshot.
+    pub timestamp: u64,
+    /// Counts up by one per snapshot sent to this subscriber, from 0.
+    pub seq: u64,
+    /// Milliseconds since this subscriber's previous snapshot; 0 on the first.
+    pub elapsed_ms: u64,
+    /// Grid ticks skipped since the previous snapshot because the collector
+    /// fell behind (e.g. the machine was suspended or a refresh stalled).
+    pub missed: u64,
+}
+
+/// Which parts of [`Sources`] a stream reads.
+#[derive(Debug, Clone, Copy, Default)]
+struct Demand {
+    cpu: bool,
+    cpu_frequency: bool,
+    /// Never implied by a stream; see [`Hub::subscribe_with_idle_states`].
+    cpu_idle: bool,
+    memory: bool,
+    processes: bool,
+    process_details: bool,
+    networks: bool,
+    disks: bool,
+    sensors: bool,
+    load: bool,
+    interrupts: bool,
+}
+
+impl Demand {
+    fn of(name: StreamName) -> Self {
+        let none = Self::default();
+        match name {
+            StreamName::Cpu => Self {
+                cpu: true,
+                cpu_frequency: true,
+                ..none
+            },
+            StreamName::Memory => Self {
+                memory: true,
+                ..none
+            },
+            StreamName::Processes => Self {
+                processes: true,
+                process_details: true,
+                ..none
+            },
+            StreamName::Overview => Self {
+                cpu: true,
+                memory: true,
+                processes: true,
+                networks: true,
+                disks: true,
+                load: true,
+                ..none
+            },
+            StreamName::Disks => Self {
+                disks: true,
+                ..none
+            },
+            StreamName::Networks => Self {
+                networks: true,
+                ..none
+            },
+            StreamName::Sensors => Self {
+                sensors: true,
+                ..none
+            },
+            StreamName::Load => Self { load: true, ..none },
+            StreamName::Interrupts => Self {
+                interrupts: true,
+                ..none
+            },
+        }
+    }
+
+    fn union(self, other: Self) -> Self {
+        Self {
+            cpu: self.cpu || other.cpu,
+            cpu_frequency: self.cpu_frequency || other.cpu_frequency,
+            cpu_idle: self.cpu_idle || other.cpu_idle,
+            memory: self.memory || other.memory,
+            processes: self.processes || other.processes,
+            process_details: self.process_details || other.process_details,
+            networks: self.networks || other.networks,
+            disks: self.disks || other.disks,
+            sensors: self.sensors || other.sensors,
+            load: self.load || other.load,
+            interrupts: self.interrupts || other.interrupts,
+        }
+    }
+
+    /// The parts of `self` that `other` doesn't already cover.
+    fn without(self, other: Self) -> Self {
+        Self {
+            cpu: self.cpu && !other.cpu,
+            cpu_frequency: self.cpu_frequency && !other.cpu_frequency,
+            cpu_idle: self.cpu_idle && !other.cpu_idle,
+            memory: self.memory && !other.memory,
+            processes: self.processes && !other.processes,
+            process_details: self.process_details && !other.process_details,
+            networks: self.networks && !other.networks,
+            disks: self.disks && !other.disks,
+            sensors: self.sensors && !other.sensors,
+            load: self.load && !other.load,
+            interrupts: self.interrupts && !other.interrupts,
+        }
+    }
+
+    /// Everything [`History::record`] reads.
+    const HISTORY: Self = Self {
+        cpu: true,
+        cpu_frequency: false,
+        cpu_idle: false,
+        memory: true,
+        processes: false,
+        process_details: false,
+        networks: true,
+        disks: true,
+        sensors: false,
+        load: false,
+        interrupts: false,
+    };
+
+    /// CPU usage (global or per process) is a delta between two refreshes.
+    fn needs_warmup(self) -> bool {
+        self.cpu || self.processes
+    }
+}
+
+/// Builds a snapshot and sends it, returning its JSON size when it was
+/// measured. An error means the subscriber is done for, most likely
+/// because its webview is gone.
+type Emit = Box<dyn FnMut(&dyn MetricsSource, Tick) -> Result<Option<usize>, String> + Send>;
+
+struct Subscriber {
+    demand: Demand,
+    stream: Arc<Stream>,
+    /// No tick is served before this (covers the CPU warm-up).
+    not_before: Instant,
+    /// Grid tick this subscriber was last served on.
+    last_served: Option<Instant>,
+    /// When it was actually served, which trails `last_served` by however
+    /// long the wake-up and refresh took.
+    last_sent: Option<Instant>,
+    /// Set once the stream backs off for a hidden window, so the gap that
+    /// causes isn't reported as missed ticks on the next snapshot.
+    backed_off: bool,
+    seq: u64,
+    emit: Emit,
+}
+
+enum Message {
+    Subscribe(Subscriber),
+    /// A stream's interval changed; recompute the next wake-up.
+    Reschedule,
+    /// Whether every window is hidden.
+    Background(bool),
+    Record(Option<Recorder>),
+    Replay(Option<Box<Replay>>),
+    Seek(u64),
+}
+
+/// Handle kept in Tauri state; the collector itself runs on its own
+/// thread, since every refresh is blocking file and syscall work.
+pub struct Hub(mpsc::Sender<Message>);
+
+impl Hub {
+    pub fn new(history: History, diagnostics: Diagnostics) -> (Self, Collector) {
+        let (sender, receiver) = mpsc::channel();
+        let epoch = Instant::now();
+        let collector = Collector {
+            sources: Sources::new(),
+            subscribers: Vec::new(),
+            receiver,
+            epoch,
+            history,
+            history_due: epoch + SAMPLE_INTERVAL,
+            processes_due: epoch + PROCESS_SAMPLE_INTERVAL,
+            background: false,
+            store: None,
+            diagnostics,
+            recorder: None,
+            replay: None,
+        };
+        (Self(sender), collector)
+    }
+
+    /// Feeds `on_event` with `build(sources, tick)` on every tick of
+    /// `stream` until it is cancelled or the channel closes.
+    pub fn subscribe<T>(
+        &self,
+        stream: Arc<Stream>,
+        on_event: Channel<T>,
+        build: impl FnMut(&dyn MetricsSource, Tick) -> T + Send + 'static,
+    ) where
+        T: IpcResponse + Serialize + Send + 'static,
+    {
+        let demand = Demand::of(stream.name());
+        self.add(demand, stream, on_event, build);
+    }
+
+    /// Like [`subscribe`](Self::subscribe), but also keeps per-core idle
+    /// state residency fresh while this subscriber is around. Reading it
+    /// is a few files per state per CPU, so it's opt-in.
+    pub fn subscribe_with_idle_states<T>(
+        &self,
+        stream: Arc<Stream>,
+        on_event: Channel<T>,
+        build: impl FnMut(&dyn MetricsSource, Tick) -> T + Send + 'static,
+    ) where
+        T: IpcResponse + Serialize + Send + 'static,
+    {
+        let demand = Demand {
+            cpu_idle: true,
+            ..Demand::of(stream.name())
+        };
+        self.add(demand, stream, on_event, build);
+    }
+
+    fn add<T>(
+        &self,
+        demand: Demand,
+        stream: Arc<Stream>,
+        on_event: Channel<T>,
+        mut build: impl FnMut(&dyn MetricsSource, Tick) -> T + Send + 'static,
+    ) where
+        T: IpcResponse + Serialize + Send + 'static,
+    {
+        let warmup = if demand.needs_warmup() {
+            MINIMUM_CPU_UPDATE_INTERVAL
+        } else {
+            Duration::ZERO
+        };
+        // A send error only means the collector is gone, i.e. the app is
+        // shutting down; the subscriber is dropped with it.
+        let mut sent = 0u64;
+        let _ = self.0.send(Message::Subscribe(Subscriber {
+            demand,
+            stream,
+            not_before: Instant::now() + warmup,
+            last_served: None,
+            last_sent: None,
+            backed_off: false,
+            seq: 0,
+            emit: Box::new(move |sources, tick| {
+                let snapshot = build(sources, tick);
+                let bytes = if sent.is_multiple_of(diagnostics::PAYLOAD_SAMPLE_EVERY) {
+                    Some(diagnostics::payload_size(&snapshot).map_err(|err| err.to_string())?)
+                } else {
+                    None
+                };
+                sent += 1;
+                on_event.send(snapshot).map_err(|err| err.to_string())?;
+                Ok(bytes)
+            }),
+        }));
+    }
+
+    pub fn reschedule(&self) {
+        let _ = self.0.send(Message::Reschedule);
+    }
+
+    /// Every window went hidden, or one came back. Implies a reschedule.
+    pub fn set_background(&self, background: bool) {
+        let _ = self.0.send(Message::Background(background));
+    }
+
+    /// Starts (replacing any running one) or stops the session recording.
+    pub fn record(&self, recorder: Option<Recorder>) {
+        let _ = self.0.send(Message::Record(recorder));
+    }
+
+    /// Switches every stream to a replay, or back to the live system.
+    pub fn replay(&self, replay: Option<Replay>) {
+        let _ = self.0.send(Message::Replay(replay.map(Box::new)));
+    }
+
+    pub fn seek_replay(&self, offset_ms: u64) {
+        let _ = self.0.send(Message::Seek(offset_ms));
+    }
+}
+
+pub struct Collector {
+    sources: Sources,
+    subscribers: Vec<Subscriber>,
+    receiver: mpsc::Receiver<Message>,
+    epoch: Instant,
+    history: History,
+    history_due: Instant,
+    /// Next history tick to also sample processes for the store on.
+    processes_due: Instant,
+    /// Every window is hidden, so nobody is watching charts fill up.
+    background: bool,
+    /// Persistent copy of the history samples, if the data dir is usable.
+    store: Option<Store>,
+    diagnostics: Diagnostics,
+    recorder: Option<Recorder>,
+    replay: Option<Box<Replay>>,
+}
+
+impl Collector {
+    pub fn set_store(&mut self, store: Store) {
+        self.store = Some(store);
+    }
+
+    /// Serves subscribers until the [`Hub`] is dropped. Blocks, so run it
+    /// on a thread of its own.
+    pub fn run(mut self) {
+        // Prime the history sources so the first recorded point is a real delta.
+        self.sources.refresh(self.history_demand(true));
+        loop {
+            let wait = self.next_due().saturating_duration_since(Instant::now());
+            let message = match self.receiver.recv_timeout(wait) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => {
+                    self.tick();
+                    continue;
+                }
+                // Hub dropped; the app is exiting.
+                Err(RecvTimeoutError::Disconnected) => return,
+            };
+            match message {
+                Message::Subscribe(subscriber) => self.add(subscriber),
+                Message::Reschedule => {}
+                Message::Background(background) => {
+                    self.background = background;
+                    // Don't wait out the slow interval once a window is back.
+                    let due = self.grid_at_or_after(Instant::now(), SAMPLE_INTERVAL);
+                    self.history_due = self.history_due.min(due);
+                }
+                Message::Record(recorder) => self.recorder = recorder,
+                Message::Replay(replay) => {
+                    if replay.is_none() && self.replay.is_some() {
+                        // Nothing but history was refreshed while replaying.
+                        self.sources.refresh(self.active_demand());
+                    }
+                    self.replay = replay;
+                }
+                Message::Seek(offset) => {
+                    if let Some(replay) = &mut self.replay {
+                        replay.seek(offset);
+                    }
+                }
+            }
+        }
+    }
+
+    fn add(&mut self, subscriber: Subscriber) {
+        // Prime whatever nobody was refreshing yet, so the first delta the
+        // new stream sees starts now rather than whenever it last ran.
+        let active = self.active_demand();
+        self.sources.refresh(subscriber.demand.without(active));
+        self.diagnostics.started(subscriber.stream.name());
+        self.subscribers.push(subscriber);
+    }
+
+    /// What a history tick refreshes; processes only for the store.
+    fn history_demand(&self, processes: bool) -> Demand {
+        Demand {
+            processes: processes && self.store.is_some(),
+            ..Demand::HISTORY
+        }
+    }
+
+    fn history_interval(&self) -> Duration {
+        if self.background {
+            HIDDEN_SAMPLE_INTERVAL
+        } else {
+            SAMPLE_INTERVAL
+        }
+    }
+
+    fn active_demand(&self) -> Demand {
+        self.subscribers
+            .iter()
+            .fold(Demand::HISTORY, |demand, sub| demand.union(sub.demand))
+    }
+
+    /// First grid point at or after `instant` for the given interval.
+    fn grid_at_or_after(&self, instant: Instant, interval: Duration) -> Instant {
+        let offset = instant.saturating_duration_since(self.epoch).as_nanos();
+        let step = interval.as_nanos().max(1);
+        let ticks = offset.div_ceil(step);
+        self.epoch + nanos(ticks * step)
+    }
+
+    /// Last grid point at or before `now` for the given interval.
+    fn latest_grid_point(&self, now: Instant, interval: Duration) -> Instant {
+        let next = self.grid_at_or_after(now, interval);
+        if next > now {
+            next - interval
+        } else {
+            next
+        }
+    }
+
+    /// `None` while the subscriber is paused.
+    fn due(&self, subscriber: &Subscriber) -> Option<Instant> {
+        let interval = subscriber.stream.effective_interval()?;
+        Some(match subscriber.last_served {
+            // Re-snap in case the interval changed since the last tick.
+            Some(last) => self.grid_at_or_after(last + interval, interval),
+            None => self.grid_at_or_after(subscriber.not_before, interval),
+        })
+    }
+
+    fn next_due(&self) -> Instant {
+        self.subscribers
+            .iter()
+            .filter_map(|sub| self.due(sub))
+            .fold(self.history_due, Instant::min)
+    }
+
+    fn tick(&mut self) {
+        let diagnostics = &self.diagnostics;
+        self.subscribers.retain(|sub| {
+            let cancelled = sub.stream.is_cancelled();
+            if cancelled {
+                diagnostics.stopped(sub.stream.name());
+            }
+            !cancelled
+        });
+        for subscriber in &mut self.subscribers {
+            subscriber.backed_off |= subscriber.stream.is_hidden();
+        }
+
+        let now = Instant::now();
+        let due: Vec<(usize, Duration)> = (0..self.subscribers.len())
+            .filter_map(|index| {
+                let subscriber = &self.subscribers[index];
+                let interval = subscriber.stream.effective_interval()?;
+                (self.due(subscriber)? <= now).then_some((index, interval))
+            })
+            .collect();
+        let record = self.history_due <= now;
+        let sample_processes = record && self.processes_due <= now;
+        let base = if record {
+            self.history_demand(sample_processes)
+        } else {
+            Demand::default()
+        };
+        let demand = if self.replay.is_some() {
+            base
+        } else {
+            due.iter().fold(base, |demand, &(index, _)| {
+                demand.union(self.subscribers[index].demand)
+            })
+        };
+        let refresh_started = Instant::now();
+        self.sources.refresh(demand);
+        let refresh = refresh_started.elapsed();
+        let timestamp = history::unix_millis();
+
+        if record {
+            let mut samples = history::sample(&self.sources);
+            self.history.record(timestamp, &samples);
+            if let Some(store) = &self.store {
+                if sample_processes {
+                    samples.extend(history::sample_processes(
+                        &self.sources,
+                        store::STORED_PROCESSES,
+                    ));
+                    self.processes_due = now + PROCESS_SAMPLE_INTERVAL;
+                }
+                if let Err(err) = store.append(timestamp, &samples) {
+                    // Most likely a full disk; stop persisting rather than
+                    // failing the same write every second.
+                    log::warn!("metric store disabled: {err}");
+                    self.store = None;
+                }
+            }
+            let interval = self.history_interval();
+            self.history_due = self.latest_grid_point(now, interval) + interval;
+        }
+
+        // One frame per snapshot tick, at whatever rate the fastest stream
+        // runs and holding what the due streams just refreshed.
+        if self.replay.is_none() && !due.is_empty() {
+            if let Some(recorder) = &mut self.recorder {
+                if let Err(err) = recorder.write(&self.sources, timestamp) {
+                    log::warn!("session recording stopped: {err}");
+                    self.recorder = None;
+                }
+            }
+        }
+
+        if let Some(replay) = &mut self.replay {
+            replay.advance(now);
+        }
+        let (source, timestamp): (&dyn MetricsSource, u64) = match &self.replay {
+            Some(replay) => (replay.as_ref(), replay.timestamp()),
+            None => (&self.sources, timestamp),
+        };
+
+        let mut closed = Vec::new();
+        for &(index, interval) in &due {
+            // Serve the latest grid point we've reached, so a slow tick
+            // skips ahead instead of replaying a burst of missed ones.
+            let served = self.latest_grid_point(now, interval);
+            let subscriber = &mut self.subscribers[index];
+            let tick = Tick {
+                timestamp,
+                seq: subscriber.seq,
+                elapsed_ms: subscriber
+                    .last_sent
+                    .map_or(0, |last| duration_ms(now.duration_since(last))),
+                missed: match subscriber.last_served {
+                    Some(last) if !subscriber.backed_off => missed_ticks(served - last, interval),
+                    _ => 0,
+                },
+            };
+            subscriber.last_served = Some(served);
+            subscriber.backed_off = subscriber.stream.is_hidden();
+            subscriber.last_sent = Some(now);
+            subscriber.seq += 1;
+            let name = subscriber.stream.name();
+            let send_started = Instant::now();
+            match (subscriber.emit)(source, tick) {
+                Ok(bytes) => {
+                    self.diagnostics
+                        .served(name, refresh, send_started.elapsed(), bytes);
+                }
+                Err(err) => {
+                    self.diagnostics.failed(name, err);
+                    closed.push(index); // Webview is gone; nobody is listening anymore.
+                }
+            }
+        }
+        for index in closed.into_iter().rev() {
+            let subscriber = self.subscribers.swap_remove(index);
+            self.diagnostics.stopped(subscriber.stream.name());
+        }
+    }
+}
+
+/// Grid points strictly between two served ones. An interval change in
+/// between re-snaps to the new grid, which this rounds down to no misses.
+fn missed_ticks(gap: Duration, interval: Duration) -> u64 {
+    let ticks = gap.as_nanos() / interval.as_nanos().max(1);
+    u64::try_from(ticks.saturating_sub(1)).unwrap_or(u64::MAX)
+}
+
+fn nanos(nanos: u128) -> Duration {
+    Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+}
@@ -0,0 +1,198 @@
+//! Temperatures, fan speeds and voltages from the Linux hwmon interface.
+//!
+//! sysinfo's `Components` covers temperatures but nothing else, and always
+//! reads the real `/sys`, so this walks `class/hwmon/hwmonN` under its own
+//! root and picks up the `tempN_*`, `fanN_*` and `inN_*` attributes. See the
+//! kernel's `Documentation/hwmon/sysfs-interface.rst`.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    source::{SensorKind, SensorReading},
+    sysfs, topology,
+};
+
+/// One sensor family, and how to turn its raw attribute values into the
+/// reading's unit.
+struct Family {
+    prefix: &'static str,
+    kind: SensorKind,
+    /// Raw units per reported unit (temperatures are in millidegrees,
+    /// voltages in millivolts).
+    scale: f32,
+    /// Fans have a configured maximum but no critical threshold.
+    has_critical: bool,
+}
+
+const FAMILIES: [Family; 3] = [
+    Family {
+        prefix: "temp",
+        kind: SensorKind::Temperature,
+        scale: 1000.0,
+        has_critical: true,
+    },
+    Family {
+        prefix: "fan",
+        kind: SensorKind::Fan,
+        scale: 1.0,
+        has_critical: false,
+    },
+    Family {
+        prefix: "in",
+        kind: SensorKind::Voltage,
+        scale: 1000.0,
+        has_critical: true,
+    },
+];
+
+/// Reads every temperature, fan and voltage sensor under a sysfs root.
+pub struct Hwmon {
+    root: PathBuf,
+    readings: Vec<SensorReading>,
+}
+
+impl Hwmon {
+    pub fn new(sysfs_root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: sysfs_root.into().join("class/hwmon"),
+            readings: Vec::new(),
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        self.readings = topology::numbered_dirs(&self.root, "hwmon")
+            .iter()
+            .flat_map(|(_, chip)| read_chip(chip))
+            .collect();
+    }
+
+    pub fn readings(&self) -> &[SensorReading] {
+        &self.readings
+    }
+}
+
+fn read_chip(chip: &Path) -> Vec<SensorReading> {
+    let chip_name = sysfs::read_string(&chip.join("name")).unwrap_or_default();
+    let Ok(entries) = fs::read_dir(chip) else {
+        return Vec::new();
+    };
+    let mut inputs: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter_map(|file| file.strip_suffix("_input").map(str::to_string))
+        .collect();
+    inputs.sort_by(|a, b| natural_key(a).cmp(&natural_key(b)));
+
+    inputs
+        .iter()
+        .filter_map(|sensor| {
+            let family = FAMILIES.iter().find(|family| {
+                sensor
+                    .strip_prefix(family.prefix)
+                    .is_some_and(|index| index.parse::<u32>().is_ok())
+            })?;
+            let value = |attribute: &str| {
+                sysfs::read_i64(&chip.join(format!("{sensor}_{attribute}")))
+                    .map(|raw| raw as f32 / family.scale)
+            };
+            let label = sysfs::read_string(&chip.join(format!("{sensor}_label")))
+                .unwrap_or_else(|| sensor.clone());
+            Some(SensorReading {
+                kind: family.kind,
+                label: if chip_name.is_empty() {
+                    label
+                } else {
+                    format!("{chip_name} {label}")
+                },
+                value: value("input")?,
+                max: value("max"),
+                critical: family.has_critical.then(|| value("crit")).flatten(),
+            })
+        })
+        .collect()
+}
+
+/// Sorts `fan10` after `fan9`.
+fn natural_key(sensor: &str) -> (&str, u32) {
+    let split = sensor
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(sensor.len());
+    let (prefix, index) = sensor.split_at(split);
+    (prefix, index.parse().unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::Fixture;
+
+    #[test]
+    fn reads_sensors_from_a_fixture_root() {
+        let fixture = Fixture::new("hwmon");
+        let nct = Path::new("class/hwmon/hwmon2");
+        // Chips come in numeric order, not hwmon10 before hwmon2.
+        let acpi = Path::new("class/hwmon/hwmon10");
+        fixture.write(nct.join("name"), "nct6798");
+        fixture.write(nct.join("fan1_input"), "1250");
+        fixture.write(nct.join("fan1_label"), "CPU fan");
+        fixture.write(nct.join("fan1_max"), "2200");
+        fixture.write(nct.join("fan10_input"), "0");
+        fixture.write(nct.join("in0_input"), "1104");
+        fixture.write(nct.join("in0_max"), "1744");
+        fixture.write(nct.join("in0_crit"), "1800");
+        fixture.write(nct.join("temp1_input"), "42000");
+        fixture.write(nct.join("temp1_label"), "SYSTIN");
+        fixture.write(nct.join("temp1_crit"), "100000");
+        // A failed read is skipped rather than reported as 0.
+        fixture.write(nct.join("temp2_label"), "CPUTIN");
+        fixture.write(acpi.join("name"), "acpitz");
+        fixture.write(acpi.join("temp1_input"), "27800");
+
+        let mut hwmon = Hwmon::new(fixture.root());
+        hwmon.refresh();
+
+        assert_eq!(
+            hwmon.readings(),
+            [
+                SensorReading {
+                    kind: SensorKind::Fan,
+                    label: "nct6798 CPU fan".to_string(),
+                    value: 1250.0,
+                    max: Some(2200.0),
+                    critical: None,
+                },
+                SensorReading {
+                    kind: SensorKind::Fan,
+                    label: "nct6798 fan10".to_string(),
+                    value: 0.0,
+                    max: None,
+                    critical: None,
+                },
+                SensorReading {
+                    kind: SensorKind::Voltage,
+                    label: "nct6798 in0".to_string(),
+                    value: 1.104,
+                    max: Some(1.744),
+                    critical: Some(1.8),
+                },
+                SensorReading {
+                    kind: SensorKind::Temperature,
+                    label: "nct6798 SYSTIN".to_string(),
+                    value: 42.0,
+                    max: None,
+                    critical: Some(100.0),
+                },
+                SensorReading {
+                    kind: SensorKind::Temperature,
+                    label: "acpitz temp1".to_string(),
+                    value: 27.8,
+                    max: None,
+                    critical: None,
+                },
+            ]
+        );
+    }
+}
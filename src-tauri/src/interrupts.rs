@@ -0,0 +1,315 @@
+//! Interrupt, softirq, context switch and fork rates.
+//!
+//! The kernel only keeps counters since boot (`/proc/stat`,
+//! `/proc/interrupts`, `/proc/softirqs`), so rates are the difference
+//! between two refreshes. Per-CPU columns make it easy to see one core
+//! taking every interrupt of a busy device. Linux only.
+
+use std::{fs, time::Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{ipc::Channel, State, Window};
+
+use crate::{
+    hub::{Hub, Tick},
+    source::MetricsSource,
+    streams::{StreamName, StreamRegistry, SubscriberId},
+};
+
+/// One row of `/proc/interrupts` or `/proc/softirqs`, as rates.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IrqRate {
+    /// IRQ number or name (`16`, `NMI`, `LOC`), or softirq type (`NET_RX`).
+    pub irq: String,
+    /// Controller, trigger and device names, e.g. "IR-PCI-MSI 327680-edge
+    /// xhci_hcd"; for named IRQs, their description. Empty for softirqs.
+    pub device: String,
+    /// Per second, in the order of [`InterruptReading::cpus`]. A single
+    /// entry for the few rows (`ERR`, `MIS`) counted system-wide.
+    pub per_cpu: Vec<f64>,
+    pub total: f64,
+}
+
+/// Events per second over the last refresh.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InterruptReading {
+    pub context_switches: f64,
+    pub interrupts: f64,
+    pub forks: f64,
+    /// Logical CPU ids of the per-CPU columns; offline CPUs are left out.
+    pub cpus: Vec<u32>,
+    /// Only IRQs that fired since the previous refresh, busiest first.
+    pub irqs: Vec<IrqRate>,
+    pub softirqs: Vec<IrqRate>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Row {
+    label: String,
+    counts: Vec<u64>,
+    device: String,
+}
+
+/// A per-CPU counter table: a `CPU0 CPU1 ...` header, then one
+/// `label: count... [description]` line per row.
+#[derive(Debug, Default, PartialEq)]
+struct Table {
+    cpus: Vec<u32>,
+    rows: Vec<Row>,
+}
+
+fn parse_table(contents: &str) -> Table {
+    let mut lines = contents.lines();
+    let cpus: Vec<u32> = lines
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .filter_map(|cpu| cpu.strip_prefix("CPU")?.parse().ok())
+        .collect();
+    let rows = lines
+        .filter_map(|line| {
+            let (label, rest) = line.split_once(':')?;
+            let mut fields = rest.split_whitespace().peekable();
+            let mut counts = Vec::with_capacity(cpus.len());
+            while counts.len() < cpus.len() {
+                let Some(count) = fields.peek().and_then(|field| field.parse().ok()) else {
+                    break;
+                };
+                counts.push(count);
+                fields.next();
+            }
+            Some(Row {
+                label: label.trim().to_string(),
+                counts,
+                device: fields.collect::<Vec<_>>().join(" "),
+            })
+        })
+        .collect();
+    Table { cpus, rows }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Counters {
+    context_switches: u64,
+    interrupts: u64,
+    forks: u64,
+    irqs: Table,
+    softirqs: Table,
+}
+
+/// The `ctxt`, `intr` and `processes` lines of `/proc/stat`.
+fn parse_stat(stat: &str, counters: &mut Counters) {
+    for line in stat.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(key), Some(value)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let Ok(value) = value.parse() else {
+            continue;
+        };
+        match key {
+            "ctxt" => counters.context_switches = value,
+            // Followed by per-IRQ counts we take from /proc/interrupts.
+            "intr" => counters.interrupts = value,
+            "processes" => counters.forks = value,
+            _ => {}
+        }
+    }
+}
+
+fn per_second(now: u64, before: u64, seconds: f64) -> f64 {
+    // Counters restart from zero when a device is re-probed.
+    now.saturating_sub(before) as f64 / seconds
+}
+
+/// Rates for every row of `now`. Rows new since `before`, or whose CPU
+/// columns changed with hotplug, count from zero for one refresh.
+fn table_rates(now: &Table, before: &Table, seconds: f64) -> Vec<IrqRate> {
+    now.rows
+        .iter()
+        .map(|row| {
+            let previous = before
+                .rows
+                .iter()
+                .find(|previous| previous.label == row.label)
+                .filter(|previous| {
+                    previous.counts.len() == row.counts.len() && now.cpus == before.cpus
+                });
+            let per_cpu: Vec<f64> = row
+                .counts
+                .iter()
+                .enumerate()
+                .map(|(cpu, &count)| {
+                    let before = previous.map_or(count, |previous| previous.counts[cpu]);
+                    per_second(count, before, seconds)
+                })
+                .collect();
+            IrqRate {
+                irq: row.label.clone(),
+                device: row.device.clone(),
+                total: per_cpu.iter().sum(),
+                per_cpu,
+            }
+        })
+        .collect()
+}
+
+fn rates(now: &Counters, before: &Counters, seconds: f64) -> InterruptReading {
+    let mut irqs: Vec<IrqRate> = table_rates(&now.irqs, &before.irqs, seconds)
+        .into_iter()
+        .filter(|irq| irq.total > 0.0)
+        .collect();
+    irqs.sort_by(|a, b| b.total.total_cmp(&a.total));
+    InterruptReading {
+        context_switches: per_second(now.context_switches, before.context_switches, seconds),
+        interrupts: per_second(now.interrupts, before.interrupts, seconds),
+        forks: per_second(now.forks, before.forks, seconds),
+        cpus: now.irqs.cpus.clone(),
+        irqs,
+        softirqs: table_rates(&now.softirqs, &before.softirqs, seconds),
+    }
+}
+
+fn read_counters() -> Option<Counters> {
+    let mut counters = Counters {
+        irqs: parse_table(&fs::read_to_string("/proc/interrupts").ok()?),
+        softirqs: parse_table(&fs::read_to_string("/proc/softirqs").ok()?),
+        ..Counters::default()
+    };
+    parse_stat(&fs::read_to_string("/proc/stat").ok()?, &mut counters);
+    Some(counters)
+}
+
+/// Rates between the last two refreshes; all zero until there have been two.
+#[derive(Default)]
+pub struct InterruptSampler {
+    previous: Option<(Instant, Counters)>,
+    reading: InterruptReading,
+}
+
+impl InterruptSampler {
+    pub fn refresh(&mut self) {
+        if !cfg!(target_os = "linux") {
+            return;
+        }
+        let Some(counters) = read_counters() else {
+            return;
+        };
+        let now = Instant::now();
+        if let Some((then, previous)) = &self.previous {
+            let seconds = now.duration_since(*then).as_secs_f64();
+            if seconds > 0.0 {
+                self.reading = rates(&counters, previous, seconds);
+            }
+        }
+        self.previous = Some((now, counters));
+    }
+
+    pub fn reading(&self) -> &InterruptReading {
+        &self.reading
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct InterruptsSnapshot {
+    #[serde(flatten)]
+    tick: Tick,
+    #[serde(flatten)]
+    reading: InterruptReading,
+}
+
+fn snapshot(source: &dyn MetricsSource, tick: Tick) -> InterruptsSnapshot {
+    InterruptsSnapshot {
+        tick,
+        reading: source.interrupts(),
+    }
+}
+
+#[tauri::command]
+pub fn get_interrupts_info(
+    registry: State<'_, StreamRegistry>,
+    hub: State<'_, Hub>,
+    window: Window,
+    on_event: Channel<InterruptsSnapshot>,
+    token: String,
+    interval_ms: Option<u64>,
+) {
+    let stream = registry.begin(
+        StreamName::Interrupts,
+        SubscriberId::new(&window, token),
+        interval_ms,
+    );
+    hub.subscribe(stream, on_event, snapshot);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_rates_from_counter_tables() {
+        let read = |interrupts: &str, softirqs: &str, stat: &str| {
+            let mut counters = Counters {
+                irqs: parse_table(interrupts),
+                softirqs: parse_table(softirqs),
+                ..Counters::default()
+            };
+            parse_stat(stat, &mut counters);
+            counters
+        };
+        let before = read(
+            "           CPU0       CPU2
+  0:         36          0  IR-IO-APIC    2-edge      timer
+ 16:        100        200  IR-IO-APIC   16-fasteoi   i801_smbus
+LOC:       5000       4000   Local timer interrupts
+ERR:          0
+",
+            "                    CPU0       CPU2
+          HI:          1          0
+      NET_RX:         10         20
+",
+            "cpu  1 2 3 4\nintr 9336 36 9\nctxt 1000\nbtime 1700000000\nprocesses 50\n",
+        );
+        assert_eq!(before.irqs.cpus, [0, 2]);
+        assert_eq!(
+            before.irqs.rows[1],
+            Row {
+                label: "16".to_string(),
+                counts: vec![100, 200],
+                device: "IR-IO-APIC 16-fasteoi i801_smbus".to_string(),
+            }
+        );
+        assert_eq!(before.irqs.rows[3].counts, [0]);
+        assert_eq!(before.context_switches, 1000);
+
+        let now = read(
+            "           CPU0       CPU2
+  0:         36          0  IR-IO-APIC    2-edge      timer
+ 16:        100       2200  IR-IO-APIC   16-fasteoi   i801_smbus
+LOC:       5500       4100   Local timer interrupts
+ERR:          0
+",
+            "                    CPU0       CPU2
+          HI:          1          0
+      NET_RX:         30         20
+",
+            "intr 11936 36 9\nctxt 3000\nprocesses 54\n",
+        );
+        let reading = rates(&now, &before, 2.0);
+        assert_eq!(reading.context_switches, 1000.0);
+        assert_eq!(reading.interrupts, 1300.0);
+        assert_eq!(reading.forks, 2.0);
+        // The idle timer and ERR rows are dropped; the storm on CPU2 leads.
+        let irqs: Vec<(&str, &[f64])> = reading
+            .irqs
+            .iter()
+            .map(|irq| (irq.irq.as_str(), irq.per_cpu.as_slice()))
+            .collect();
+        assert_eq!(
+            irqs,
+            [("16", &[0.0, 1000.0][..]), ("LOC", &[250.0, 50.0][..])]
+        );
+        assert_eq!(reading.softirqs[1].irq, "NET_RX");
+        assert_eq!(reading.softirqs[1].total, 10.0);
+    }
+}
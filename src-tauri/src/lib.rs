@@ -1,36 +1,99 @@
+mod arm;
 mod cpu;
+mod cpufreq;
 mod cpuid;
+mod cpuidle;
+mod cputime;
+mod diagnostics;
 mod disks;
+mod environment;
+mod error;
+mod history;
+mod hub;
+mod hwmon;
+mod interrupts;
+mod load;
 mod memory;
 mod network;
 mod overview;
+mod pressure;
 mod processes;
+mod sensors;
+mod session;
+mod source;
+mod store;
 mod streams;
+mod sysfs;
 mod system;
+mod topology;
+mod vulnerabilities;
+
+use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let history = history::History::default();
+    let diagnostics = diagnostics::Diagnostics::default();
+    let (hub, collector) = hub::Hub::new(history.clone(), diagnostics.clone());
     tauri::Builder::default()
         .manage(streams::StreamRegistry::default())
+        .manage(hub)
+        .manage(history)
+        .manage(diagnostics)
+        .on_window_event(streams::on_window_event)
         .invoke_handler(tauri::generate_handler![
             system::get_system_info,
             cpu::get_cpu_static,
             cpu::get_cpu_info,
+            cpufreq::set_cpu_governor,
             memory::get_memory_info,
             processes::get_processes_info,
             processes::kill_process,
             overview::get_overview_info,
             disks::get_disks_info,
             network::get_network_info,
+            sensors::get_sensors_info,
+            load::get_load_info,
+            interrupts::get_interrupts_info,
             streams::stop_stream,
+            streams::resync_stream,
+            streams::set_stream_interval,
+            streams::set_window_occluded,
+            diagnostics::get_stream_status,
+            session::start_recording,
+            session::stop_recording,
+            session::start_replay,
+            session::stop_replay,
+            session::seek_replay,
+            history::get_history,
+            store::query_metrics,
         ])
-        .setup(|_app| {
-            #[cfg(debug_assertions)]
-            {
-                use tauri::Manager;
-                if let Some(window) = _app.get_webview_window("main") {
-                    window.open_devtools();
+        .setup(|app| {
+            let mut collector = collector;
+            let store = app
+                .path()
+                .app_data_dir()
+                .map_err(std::io::Error::other)
+                .and_then(|dir| store::Store::open(&dir.join("metrics")));
+            let store = match store {
+                Ok(store) => {
+                    collector.set_store(store.clone());
+                    Some(store)
+                }
+                // Live monitoring still works without persistence.
+                Err(err) => {
+                    log::warn!("metric store unavailable: {err}");
+                    None
                 }
+            };
+            app.manage(store);
+            std::thread::Builder::new()
+                .name("collector".to_string())
+                .spawn(move || collector.run())?;
+
+            #[cfg(debug_assertions)]
+            if let Some(window) = app.get_webview_window("main") {
+                window.open_devtools();
             }
             Ok(())
         })
@@ -0,0 +1,43 @@
+use serde::Serialize;
+use tauri::{ipc::Channel, State, Window};
+
+use crate::{
+    hub::{Hub, Tick},
+    pressure::{LoadAverage, PressureReading},
+    source::MetricsSource,
+    streams::{StreamName, StreamRegistry, SubscriberId},
+};
+
+/// Whether the machine is keeping up, as opposed to how busy it is.
+#[derive(Debug, Serialize, Clone)]
+pub struct LoadSnapshot {
+    #[serde(flatten)]
+    tick: Tick,
+    load_average: LoadAverage,
+    pressure: PressureReading,
+}
+
+fn snapshot(source: &dyn MetricsSource, tick: Tick) -> LoadSnapshot {
+    LoadSnapshot {
+        tick,
+        load_average: source.load_average(),
+        pressure: source.pressure(),
+    }
+}
+
+#[tauri::command]
+pub fn get_load_info(
+    registry: State<'_, StreamRegistry>,
+    hub: State<'_, Hub>,
+    window: Window,
+    on_event: Channel<LoadSnapshot>,
+    token: String,
+    interval_ms: Option<u64>,
+) {
+    let stream = registry.begin(
+        StreamName::Load,
+        SubscriberId::new(&window, token),
+        interval_ms,
+    );
+    hub.subscribe(stream, on_event, snapshot);
+}
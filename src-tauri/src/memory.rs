@@ -1,13 +1,16 @@
-use std::sync::atomic::Ordering;
-
 use serde::Serialize;
-use sysinfo::{MemoryRefreshKind, RefreshKind, System};
-use tauri::{ipc::Channel, State};
+use tauri::{ipc::Channel, State, Window};
 
-use crate::streams::{StreamName, StreamRegistry, SAMPLE_INTERVAL};
+use crate::{
+    hub::{Hub, Tick},
+    source::MetricsSource,
+    streams::{StreamName, StreamRegistry, SubscriberId},
+};
 
 #[derive(Debug, Serialize, Clone)]
 pub struct MemorySnapshot {
+    #[serde(flatten)]
+    tick: Tick,
     total: u64,
     used: u64,
     free: u64,
@@ -17,33 +20,33 @@ pub struct MemorySnapshot {
     swap_free: u64,
 }
 
+fn snapshot(source: &dyn MetricsSource, tick: Tick) -> MemorySnapshot {
+    let memory = source.memory();
+    MemorySnapshot {
+        tick,
+        total: memory.total,
+        used: memory.used,
+        free: memory.free,
+        available: memory.available,
+        swap_total: memory.swap_total,
+        swap_used: memory.swap_used,
+        swap_free: memory.swap_free,
+    }
+}
+
 #[tauri::command]
-pub async fn get_memory_info(
+pub fn get_memory_info(
     registry: State<'_, StreamRegistry>,
+    hub: State<'_, Hub>,
+    window: Window,
     on_event: Channel<MemorySnapshot>,
-) -> Result<(), String> {
-    let cancelled = registry.begin(StreamName::Memory);
-    let mut sys = System::new_with_specifics(
-        RefreshKind::nothing().with_memory(MemoryRefreshKind::everything()),
+    token: String,
+    interval_ms: Option<u64>,
+) {
+    let stream = registry.begin(
+        StreamName::Memory,
+        SubscriberId::new(&window, token),
+        interval_ms,
     );
-
-    while !cancelled.load(Ordering::Relaxed) {
-        sys.refresh_memory();
-
-        let snapshot = MemorySnapshot {
-            total: sys.total_memory(),
-            used: sys.used_memory(),
-            free: sys.free_memory(),
-            available: sys.available_memory(),
-            swap_total: sys.total_swap(),
-            swap_used: sys.used_swap(),
-            swap_free: sys.free_swap(),
-        };
-
-        if on_event.send(snapshot).is_err() {
-            break; // Webview is gone; nobody is listening anymore.
-        }
-        tokio::time::sleep(SAMPLE_INTERVAL).await;
-    }
-    Ok(())
+    hub.subscribe(stream, on_event, snapshot);
 }
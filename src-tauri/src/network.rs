@@ -1,48 +1,58 @@
-use std::sync::atomic::Ordering;
-
 use serde::Serialize;
-use sysinfo::Networks;
-use tauri::{ipc::Channel, State};
+use tauri::{ipc::Channel, State, Window};
 
-use crate::streams::{StreamName, StreamRegistry, SAMPLE_INTERVAL};
+use crate::{
+    hub::{Hub, Tick},
+    source::MetricsSource,
+    streams::{StreamName, StreamRegistry, SubscriberId},
+};
 
 #[derive(Debug, Serialize, Clone)]
 pub struct NetworkInfo {
     name: String,
-    /// Bytes received since the previous 1-second refresh, i.e. ~bytes/sec.
+    /// Bytes per second since the previous refresh.
     received_rate: u64,
     transmitted_rate: u64,
     total_received: u64,
     total_transmitted: u64,
 }
 
-#[tauri::command]
-pub async fn get_network_info(
-    registry: State<'_, StreamRegistry>,
-    on_event: Channel<Vec<NetworkInfo>>,
-) -> Result<(), String> {
-    let cancelled = registry.begin(StreamName::Networks);
-    let mut networks = Networks::new_with_refreshed_list();
-
-    while !cancelled.load(Ordering::Relaxed) {
-        networks.refresh(true);
+#[derive(Debug, Serialize, Clone)]
+pub struct NetworksSnapshot {
+    #[serde(flatten)]
+    tick: Tick,
+    interfaces: Vec<NetworkInfo>,
+}
 
-        let mut snapshot: Vec<NetworkInfo> = networks
-            .iter()
-            .map(|(name, data)| NetworkInfo {
-                name: name.clone(),
-                received_rate: data.received(),
-                transmitted_rate: data.transmitted(),
-                total_received: data.total_received(),
-                total_transmitted: data.total_transmitted(),
-            })
-            .collect();
-        snapshot.sort_by(|a, b| a.name.cmp(&b.name));
+fn snapshot(source: &dyn MetricsSource, tick: Tick) -> NetworksSnapshot {
+    let mut interfaces: Vec<NetworkInfo> = source
+        .networks()
+        .into_iter()
+        .map(|network| NetworkInfo {
+            name: network.name,
+            received_rate: network.received_rate,
+            transmitted_rate: network.transmitted_rate,
+            total_received: network.total_received,
+            total_transmitted: network.total_transmitted,
+        })
+        .collect();
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    NetworksSnapshot { tick, interfaces }
+}
 
-        if on_event.send(snapshot).is_err() {
-            break; // Webview is gone; nobody is listening anymore.
-        }
-        tokio::time::sleep(SAMPLE_INTERVAL).await;
-    }
-    Ok(())
+#[tauri::command]
+pub fn get_network_info(
+    registry: State<'_, StreamRegistry>,
+    hub: State<'_, Hub>,
+    window: Window,
+    on_event: Channel<NetworksSnapshot>,
+    token: String,
+    interval_ms: Option<u64>,
+) {
+    let stream = registry.begin(
+        StreamName::Networks,
+        SubscriberId::new(&window, token),
+        interval_ms,
+    );
+    hub.subscribe(stream, on_event, snapshot);
 }
@@ -1,13 +1,12 @@
-use std::sync::atomic::Ordering;
-
 use serde::Serialize;
-use sysinfo::{
-    CpuRefreshKind, Disks, MemoryRefreshKind, Networks, ProcessRefreshKind, ProcessesToUpdate,
-    RefreshKind, System, MINIMUM_CPU_UPDATE_INTERVAL,
-};
-use tauri::{ipc::Channel, State};
+use tauri::{ipc::Channel, State, Window};
 
-use crate::streams::{StreamName, StreamRegistry, SAMPLE_INTERVAL};
+use crate::{
+    hub::{Hub, Tick},
+    pressure::{LoadAverage, PressureReading},
+    source::MetricsSource,
+    streams::{StreamName, StreamRegistry, SubscriberId},
+};
 
 #[derive(Debug, Serialize, Clone)]
 pub struct TopProcess {
@@ -18,9 +17,11 @@ pub struct TopProcess {
 }
 
 /// Aggregate snapshot backing the dashboard and the status footer.
-/// All rates are bytes since the previous 1-second refresh, i.e. ~bytes/sec.
+/// All rates are bytes per second.
 #[derive(Debug, Serialize, Clone)]
 pub struct OverviewSnapshot {
+    #[serde(flatten)]
+    tick: Tick,
     cpu_global: f32,
     core_usages: Vec<f32>,
     mem_total: u64,
@@ -33,82 +34,136 @@ pub struct OverviewSnapshot {
     net_tx: u64,
     disk_read: u64,
     disk_write: u64,
+    load_average: LoadAverage,
+    pressure: PressureReading,
 }
 
 fn is_loopback(name: &str) -> bool {
     name == "lo" || name.to_ascii_lowercase().contains("loopback")
 }
 
+fn snapshot(source: &dyn MetricsSource, tick: Tick) -> OverviewSnapshot {
+    let memory = source.memory();
+
+    let top = source
+        .top_processes(5)
+        .into_iter()
+        .map(|process| TopProcess {
+            pid: process.pid,
+            name: process.name,
+            cpu_usage: process.cpu_usage,
+            memory: process.memory,
+        })
+        .collect();
+
+    let (net_rx, net_tx) = source
+        .networks()
+        .iter()
+        .filter(|network| !is_loopback(&network.name))
+        .fold((0u64, 0u64), |(rx, tx), network| {
+            (rx + network.received_rate, tx + network.transmitted_rate)
+        });
+
+    let (disk_read, disk_write) = source
+        .disks()
+        .iter()
+        .fold((0u64, 0u64), |(read, write), disk| {
+            (read + disk.read_rate, write + disk.write_rate)
+        });
+
+    OverviewSnapshot {
+        tick,
+        cpu_global: source.global_cpu_usage(),
+        core_usages: source.cpus().iter().map(|cpu| cpu.usage).collect(),
+        mem_total: memory.total,
+        mem_used: memory.used,
+        swap_total: memory.swap_total,
+        swap_used: memory.swap_used,
+        process_count: source.process_count(),
+        top_processes: top,
+        net_rx,
+        net_tx,
+        disk_read,
+        disk_write,
+        load_average: source.load_average(),
+        pressure: source.pressure(),
+    }
+}
+
 #[tauri::command]
-pub async fn get_overview_info(
+pub fn get_overview_info(
     registry: State<'_, StreamRegistry>,
+    hub: State<'_, Hub>,
+    window: Window,
     on_event: Channel<OverviewSnapshot>,
-) -> Result<(), String> {
-    let cancelled = registry.begin(StreamName::Overview);
-
-    let base_refresh = RefreshKind::nothing()
-        .with_cpu(CpuRefreshKind::nothing().with_cpu_usage())
-        .with_memory(MemoryRefreshKind::everything());
-    let process_refresh = ProcessRefreshKind::nothing().with_cpu().with_memory();
-
-    let mut sys = System::new_with_specifics(base_refresh);
-    let mut networks = Networks::new_with_refreshed_list();
-    let mut disks = Disks::new_with_refreshed_list();
-
-    // Usage values are deltas between two refreshes.
-    tokio::time::sleep(MINIMUM_CPU_UPDATE_INTERVAL).await;
-
-    while !cancelled.load(Ordering::Relaxed) {
-        sys.refresh_specifics(base_refresh);
-        sys.refresh_processes_specifics(ProcessesToUpdate::All, true, process_refresh);
-        networks.refresh(true);
-        disks.refresh(true);
-
-        let mut top: Vec<TopProcess> = sys
-            .processes()
-            .iter()
-            .map(|(pid, process)| TopProcess {
-                pid: pid.as_u32(),
-                name: process.name().to_string_lossy().into_owned(),
-                cpu_usage: process.cpu_usage(),
-                memory: process.memory(),
-            })
-            .collect();
-        let process_count = top.len();
-        top.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
-        top.truncate(5);
-
-        let (net_rx, net_tx) = networks
-            .iter()
-            .filter(|(name, _)| !is_loopback(name))
-            .fold((0u64, 0u64), |(rx, tx), (_, data)| {
-                (rx + data.received(), tx + data.transmitted())
-            });
-
-        let (disk_read, disk_write) = disks.iter().fold((0u64, 0u64), |(read, write), disk| {
-            let usage = disk.usage();
-            (read + usage.read_bytes, write + usage.written_bytes)
-        });
+    token: String,
+    interval_ms: Option<u64>,
+) {
+    let stream = registry.begin(
+        StreamName::Overview,
+        SubscriberId::new(&window, token),
+        interval_ms,
+    );
+    hub.subscribe(stream, on_event, snapshot);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::{
+        scripted::{Frame, ScriptedSource},
+        NetworkReading, ProcessReading,
+    };
 
-        let snapshot = OverviewSnapshot {
-            cpu_global: sys.global_cpu_usage(),
-            core_usages: sys.cpus().iter().map(|cpu| cpu.cpu_usage()).collect(),
-            mem_total: sys.total_memory(),
-            mem_used: sys.used_memory(),
-            swap_total: sys.total_swap(),
-            swap_used: sys.used_swap(),
-            process_count,
-            top_processes: top,
-            net_rx,
-            net_tx,
-            disk_read,
-            disk_write,
-        };
-
-        if on_event.send(snapshot).is_err() {
-            break; // Webview is gone; nobody is listening anymore.
+    fn interface(name: &str, rx: u64, tx: u64) -> NetworkReading {
+        NetworkReading {
+            name: name.to_string(),
+            received_rate: rx,
+            transmitted_rate: tx,
+            ..NetworkReading::default()
         }
-        tokio::time::sleep(SAMPLE_INTERVAL).await;
     }
-    Ok(())
+
+    fn process(pid: u32, cpu_usage: f32) -> ProcessReading {
+        ProcessReading {
+            pid,
+            name: format!("proc{pid}"),
+            cpu_usage,
+            ..ProcessReading::default()
+        }
+    }
+
+    #[test]
+    fn loopback_is_excluded_from_network_totals() {
+        let source = ScriptedSource::from(Frame {
+            networks: vec![
+                interface("lo", 1_000_000, 1_000_000),
+                interface("eth0", 300, 30),
+                interface("Loopback Pseudo-Interface 1", 500_000, 500_000),
+                interface("wlan0", 200, 20),
+            ],
+            ..Frame::default()
+        });
+
+        let snapshot = snapshot(&source, Tick::default());
+        assert_eq!(snapshot.net_rx, 500);
+        assert_eq!(snapshot.net_tx, 50);
+    }
+
+    #[test]
+    fn top_processes_are_sorted_and_truncated() {
+        let usages = [3.0, 50.0, 0.0, 12.5, 99.0, 7.0, 12.0];
+        let source = ScriptedSource::from(Frame {
+            processes: (1..)
+                .zip(usages)
+                .map(|(pid, usage)| process(pid, usage))
+                .collect(),
+            ..Frame::default()
+        });
+
+        let snapshot = snapshot(&source, Tick::default());
+        assert_eq!(snapshot.process_count, usages.len());
+        let top: Vec<u32> = snapshot.top_processes.iter().map(|p| p.pid).collect();
+        assert_eq!(top, [5, 2, 4, 7, 6]);
+    }
 }
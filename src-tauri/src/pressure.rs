@@ -0,0 +1,149 @@
+//! Saturation signals: load averages and pressure stall information.
+//!
+//! CPU percentage says how busy the machine is; these say whether work is
+//! waiting. PSI (`/proc/pressure/*`, Linux 4.20+) reports the share of time
+//! in which *some* task, or *all* non-idle tasks (`full`), were stalled on a
+//! resource. Load averages come from sysinfo and are all zero on Windows.
+
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::System;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+}
+
+/// One line of a PSI file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct StallTime {
+    /// Percent of the last 10, 60 and 300 seconds spent stalled.
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+    /// Total stall time in microseconds since boot.
+    pub total: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pressure {
+    pub some: StallTime,
+    /// Not reported for CPU on kernels before 5.13.
+    pub full: Option<StallTime>,
+}
+
+/// `None` for resources the kernel doesn't report (no PSI support, PSI
+/// disabled with `psi=0`, or not Linux).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PressureReading {
+    pub cpu: Option<Pressure>,
+    pub memory: Option<Pressure>,
+    pub io: Option<Pressure>,
+}
+
+fn parse_stall_time(fields: &str) -> Option<StallTime> {
+    let mut stall = StallTime::default();
+    for field in fields.split_whitespace() {
+        let (key, value) = field.split_once('=')?;
+        match key {
+            "avg10" => stall.avg10 = value.parse().ok()?,
+            "avg60" => stall.avg60 = value.parse().ok()?,
+            "avg300" => stall.avg300 = value.parse().ok()?,
+            "total" => stall.total = value.parse().ok()?,
+            _ => {}
+        }
+    }
+    Some(stall)
+}
+
+/// Parses a `/proc/pressure/<resource>` file.
+fn parse_pressure(contents: &str) -> Option<Pressure> {
+    let mut some = None;
+    let mut full = None;
+    for line in contents.lines() {
+        match line.split_once(' ') {
+            Some(("some", fields)) => some = parse_stall_time(fields),
+            Some(("full", fields)) => full = parse_stall_time(fields),
+            _ => {}
+        }
+    }
+    Some(Pressure { some: some?, full })
+}
+
+fn read_pressure(resource: &str) -> Option<Pressure> {
+    parse_pressure(&fs::read_to_string(format!("/proc/pressure/{resource}")).ok()?)
+}
+
+/// The latest load averages and PSI, as of the last refresh.
+#[derive(Default)]
+pub struct PressureSampler {
+    load_average: LoadAverage,
+    pressure: PressureReading,
+}
+
+impl PressureSampler {
+    pub fn refresh(&mut self) {
+        let load = System::load_average();
+        self.load_average = LoadAverage {
+            one: load.one,
+            five: load.five,
+            fifteen: load.fifteen,
+        };
+        if cfg!(target_os = "linux") {
+            self.pressure = PressureReading {
+                cpu: read_pressure("cpu"),
+                memory: read_pressure("memory"),
+                io: read_pressure("io"),
+            };
+        }
+    }
+
+    pub fn load_average(&self) -> LoadAverage {
+        self.load_average
+    }
+
+    pub fn pressure(&self) -> PressureReading {
+        self.pressure
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_some_and_full_lines() {
+        let memory = "\
+some avg10=1.53 avg60=0.87 avg300=0.22 total=4190518
+full avg10=0.50 avg60=0.11 avg300=0.03 total=1553082
+";
+        assert_eq!(
+            parse_pressure(memory),
+            Some(Pressure {
+                some: StallTime {
+                    avg10: 1.53,
+                    avg60: 0.87,
+                    avg300: 0.22,
+                    total: 4_190_518,
+                },
+                full: Some(StallTime {
+                    avg10: 0.5,
+                    avg60: 0.11,
+                    avg300: 0.03,
+                    total: 1_553_082,
+                }),
+            })
+        );
+
+        // CPU pressure before 5.13 has no `full` line.
+        let cpu = "some avg10=0.00 avg60=0.00 avg300=0.00 total=0\n";
+        assert_eq!(
+            parse_pressure(cpu).map(|pressure| pressure.full),
+            Some(None)
+        );
+        assert_eq!(parse_pressure(""), None);
+    }
+}
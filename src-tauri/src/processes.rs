@@ -1,13 +1,14 @@
-use std::sync::atomic::Ordering;
+use std::{collections::HashMap, io};
 
 use serde::Serialize;
-use sysinfo::{
-    Pid, ProcessRefreshKind, ProcessesToUpdate, RefreshKind, Signal, System, UpdateKind,
-    MINIMUM_CPU_UPDATE_INTERVAL,
-};
-use tauri::{ipc::Channel, State};
+use tauri::{ipc::Channel, State, Window};
 
-use crate::streams::{StreamName, StreamRegistry, SAMPLE_INTERVAL};
+use crate::{
+    error::{Error, Result},
+    hub::{Hub, Tick},
+    source::MetricsSource,
+    streams::{StreamName, StreamRegistry, SubscriberId},
+};
 
 #[derive(Debug, Serialize, Clone)]
 pub struct ProcessInfo {
@@ -18,97 +19,308 @@ pub struct ProcessInfo {
     cpu_usage: f32,
     memory: u64,
     virtual_memory: u64,
-    run_time: u64,
+    /// Unix seconds; the frontend derives run time from it and each
+    /// tick's timestamp.
+    start_time: u64,
     parent: Option<u32>,
     status: String,
 }
 
-fn process_refresh_kind() -> ProcessRefreshKind {
-    // Only what the UI shows — everything() would also collect environ,
-    // cwd, disk usage, etc. for every process on every tick.
-    ProcessRefreshKind::nothing()
-        .with_cpu()
-        .with_memory()
-        .with_cmd(UpdateKind::OnlyIfNotSet)
-        .with_exe(UpdateKind::OnlyIfNotSet)
+/// The fields of a known process that changed since the previous tick.
+#[derive(Debug, Serialize, Clone)]
+pub struct ProcessChange {
+    pid: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cpu_usage: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    virtual_memory: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<String>,
 }
 
-#[tauri::command]
-pub async fn get_processes_info(
-    registry: State<'_, StreamRegistry>,
-    on_event: Channel<Vec<ProcessInfo>>,
-) -> Result<(), String> {
-    let cancelled = registry.begin(StreamName::Processes);
-    let refresh = process_refresh_kind();
-    let mut sys = System::new_with_specifics(RefreshKind::nothing().with_processes(refresh));
-
-    // Per-process CPU usage is a delta between two refreshes.
-    tokio::time::sleep(MINIMUM_CPU_UPDATE_INTERVAL).await;
-
-    while !cancelled.load(Ordering::Relaxed) {
-        sys.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
-
-        let processes: Vec<ProcessInfo> = sys
-            .processes()
-            .iter()
-            .map(|(pid, process)| ProcessInfo {
-                pid: pid.as_u32(),
-                name: process.name().to_string_lossy().into_owned(),
-                cmd: process
-                    .cmd()
-                    .iter()
-                    .map(|arg| arg.to_string_lossy().into_owned())
-                    .collect(),
-                exe: process
-                    .exe()
-                    .map(|path| path.to_string_lossy().into_owned()),
-                cpu_usage: process.cpu_usage(),
-                memory: process.memory(),
-                virtual_memory: process.virtual_memory(),
-                run_time: process.run_time(),
-                parent: process.parent().map(|parent| parent.as_u32()),
-                status: process.status().to_string(),
-            })
-            .collect();
-
-        if on_event.send(processes).is_err() {
-            break; // Webview is gone; nobody is listening anymore.
+/// A full table first (and on resync), deltas after that. The tick's `seq`
+/// counts up by one per message, so a gap tells the frontend to call
+/// `resync_stream`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProcessesMessage {
+    Snapshot {
+        #[serde(flatten)]
+        tick: Tick,
+        processes: Vec<ProcessInfo>,
+    },
+    Delta {
+        #[serde(flatten)]
+        tick: Tick,
+        /// New processes, plus known PIDs whose identity changed (exec,
+        /// reparenting, PID reuse); always full records.
+        added: Vec<ProcessInfo>,
+        removed: Vec<u32>,
+        changed: Vec<ProcessChange>,
+    },
+}
+
+fn diff(
+    tick: Tick,
+    previous: &HashMap<u32, ProcessInfo>,
+    current: &HashMap<u32, ProcessInfo>,
+) -> ProcessesMessage {
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    for (pid, process) in current {
+        let Some(old) = previous.get(pid) else {
+            added.push(process.clone());
+            continue;
+        };
+        if old.name != process.name
+            || old.cmd != process.cmd
+            || old.exe != process.exe
+            || old.start_time != process.start_time
+            || old.parent != process.parent
+        {
+            added.push(process.clone());
+            continue;
         }
-        tokio::time::sleep(SAMPLE_INTERVAL).await;
+        let change = ProcessChange {
+            pid: *pid,
+            cpu_usage: (old.cpu_usage != process.cpu_usage).then_some(process.cpu_usage),
+            memory: (old.memory != process.memory).then_some(process.memory),
+            virtual_memory: (old.virtual_memory != process.virtual_memory)
+                .then_some(process.virtual_memory),
+            status: (old.status != process.status).then(|| process.status.clone()),
+        };
+        if change.cpu_usage.is_some()
+            || change.memory.is_some()
+            || change.virtual_memory.is_some()
+            || change.status.is_some()
+        {
+            changed.push(change);
+        }
+    }
+    let removed = previous
+        .keys()
+        .filter(|pid| !current.contains_key(pid))
+        .copied()
+        .collect();
+    ProcessesMessage::Delta {
+        tick,
+        added,
+        removed,
+        changed,
     }
-    Ok(())
+}
+
+fn process_table(source: &dyn MetricsSource) -> HashMap<u32, ProcessInfo> {
+    source
+        .processes()
+        .into_iter()
+        .map(|process| {
+            let info = ProcessInfo {
+                pid: process.pid,
+                name: process.name,
+                cmd: process.cmd,
+                exe: process.exe,
+                cpu_usage: process.cpu_usage,
+                memory: process.memory,
+                virtual_memory: process.virtual_memory,
+                start_time: process.start_time,
+                parent: process.parent,
+                status: process.status,
+            };
+            (info.pid, info)
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub fn get_processes_info(
+    registry: State<'_, StreamRegistry>,
+    hub: State<'_, Hub>,
+    window: Window,
+    on_event: Channel<ProcessesMessage>,
+    token: String,
+    interval_ms: Option<u64>,
+) {
+    let stream = registry.begin(
+        StreamName::Processes,
+        SubscriberId::new(&window, token),
+        interval_ms,
+    );
+    let control = stream.clone();
+    let mut previous: Option<HashMap<u32, ProcessInfo>> = None;
+    hub.subscribe(stream, on_event, move |sources, tick| {
+        let current = process_table(sources);
+        let message = match previous.as_ref() {
+            Some(previous) if !control.take_resync() => diff(tick, previous, &current),
+            _ => ProcessesMessage::Snapshot {
+                tick,
+                processes: current.values().cloned().collect(),
+            },
+        };
+        previous = Some(current);
+        message
+    });
 }
 
 #[tauri::command]
-pub async fn kill_process(pid: u32, force: bool) -> Result<(), String> {
+pub async fn kill_process(pid: u32, force: bool) -> Result<()> {
     if pid == std::process::id() {
-        return Err("Refusing to terminate Monsoon itself".to_string());
+        return Err(Error::ProtectedProcess {
+            pid,
+            reason: "Monsoon itself",
+        });
     }
     if pid <= 1 {
-        return Err("Refusing to terminate a system-critical process".to_string());
+        return Err(Error::ProtectedProcess {
+            pid,
+            reason: "a system-critical process",
+        });
+    }
+    terminate(pid, force)
+}
+
+fn not_found(pid: u32) -> Error {
+    Error::NotFound {
+        what: format!("Process {pid}"),
+    }
+}
+
+fn permission_denied(pid: u32) -> Error {
+    Error::PermissionDenied {
+        action: format!("signal process {pid}"),
+    }
+}
+
+/// Any other OS failure, still naming the process it was about.
+#[cfg(any(unix, windows))]
+fn signal_failed(pid: u32, err: io::Error) -> Error {
+    Error::from(io::Error::new(
+        err.kind(),
+        format!("Could not signal process {pid}: {err}"),
+    ))
+}
+
+/// Defaults to a graceful SIGTERM so the target can clean up and flush; the
+/// UI offers an explicit "Force kill" escalation for SIGKILL.
+#[cfg(unix)]
+fn terminate(pid: u32, force: bool) -> Result<()> {
+    let target = libc::pid_t::try_from(pid).map_err(|_| not_found(pid))?;
+    let signal = if force { libc::SIGKILL } else { libc::SIGTERM };
+    // SAFETY: kill(2) only takes integers.
+    if unsafe { libc::kill(target, signal) } == 0 {
+        return Ok(());
+    }
+    let err = io::Error::last_os_error();
+    Err(match err.raw_os_error() {
+        Some(libc::ESRCH) => not_found(pid),
+        Some(libc::EPERM) => permission_denied(pid),
+        _ => signal_failed(pid, err),
+    })
+}
+
+/// Windows has no graceful terminate for arbitrary processes, so `force`
+/// makes no difference.
+#[cfg(windows)]
+fn terminate(pid: u32, _force: bool) -> Result<()> {
+    use windows_sys::Win32::{
+        Foundation::{CloseHandle, ERROR_ACCESS_DENIED, ERROR_INVALID_PARAMETER},
+        System::Threading::{OpenProcess, TerminateProcess, PROCESS_TERMINATE},
+    };
+
+    let error = |err: io::Error| match u32::try_from(err.raw_os_error().unwrap_or(0)) {
+        // What OpenProcess fails with for a PID that doesn't exist.
+        Ok(ERROR_INVALID_PARAMETER) => not_found(pid),
+        Ok(ERROR_ACCESS_DENIED) => permission_denied(pid),
+        _ => signal_failed(pid, err),
+    };
+    // SAFETY: the handle is checked before use and closed exactly once.
+    unsafe {
+        let process = OpenProcess(PROCESS_TERMINATE, 0, pid);
+        if process.is_null() {
+            return Err(error(io::Error::last_os_error()));
+        }
+        let terminated = TerminateProcess(process, 1) != 0;
+        let err = io::Error::last_os_error();
+        CloseHandle(process);
+        if terminated {
+            Ok(())
+        } else {
+            Err(error(err))
+        }
     }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn terminate(_pid: u32, _force: bool) -> Result<()> {
+    Err(Error::Unsupported {
+        feature: "Process control".to_string(),
+    })
+}
 
-    let target = Pid::from_u32(pid);
-    let mut sys = System::new();
-    sys.refresh_processes(ProcessesToUpdate::Some(&[target]), false);
-
-    let process = sys
-        .process(target)
-        .ok_or_else(|| format!("Process {pid} not found"))?;
-
-    // Default to a graceful SIGTERM so the target can clean up and flush; the
-    // UI offers an explicit "Force kill" escalation for SIGKILL. `kill_with`
-    // returns `None` on platforms without the signal (e.g. Windows), where we
-    // fall back to the platform default terminate.
-    let sent = if force {
-        process.kill()
-    } else {
-        process.kill_with(Signal::Term).unwrap_or_else(|| process.kill())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::{
+        scripted::{Frame, ScriptedSource},
+        ProcessReading,
     };
 
-    if sent {
-        Ok(())
-    } else {
-        Err(format!("Failed to send kill signal to process {pid}"))
+    fn process(pid: u32, cmd: &str, cpu_usage: f32) -> ProcessReading {
+        ProcessReading {
+            pid,
+            name: cmd.to_string(),
+            cmd: vec![cmd.to_string()],
+            cpu_usage,
+            ..ProcessReading::default()
+        }
+    }
+
+    #[test]
+    fn delta_reports_added_removed_and_changed() {
+        let mut source = ScriptedSource::new(vec![
+            Frame {
+                processes: vec![
+                    process(1, "init", 0.0),
+                    process(2, "shell", 1.0),
+                    process(3, "editor", 5.0),
+                    process(4, "idle", 0.0),
+                ],
+                ..Frame::default()
+            },
+            Frame {
+                processes: vec![
+                    process(1, "init", 0.0),
+                    // exec'd into something else under the same PID
+                    process(2, "make", 1.0),
+                    process(3, "editor", 9.0),
+                    process(5, "compiler", 80.0),
+                ],
+                ..Frame::default()
+            },
+        ]);
+
+        let previous = process_table(&source);
+        source.advance();
+        let current = process_table(&source);
+
+        let ProcessesMessage::Delta {
+            mut added,
+            removed,
+            changed,
+            ..
+        } = diff(Tick::default(), &previous, &current)
+        else {
+            panic!("expected a delta");
+        };
+        added.sort_by_key(|process| process.pid);
+        let added: Vec<u32> = added.iter().map(|process| process.pid).collect();
+        assert_eq!(added, [2, 5]);
+        assert_eq!(removed, [4]);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].pid, 3);
+        assert_eq!(changed[0].cpu_usage, Some(9.0));
+        assert_eq!(changed[0].memory, None);
     }
 }
@@ -0,0 +1,57 @@
+use serde::Serialize;
+use tauri::{ipc::Channel, State, Window};
+
+use crate::{
+    hub::{Hub, Tick},
+    source::{MetricsSource, SensorKind},
+    streams::{StreamName, StreamRegistry, SubscriberId},
+};
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SensorInfo {
+    kind: SensorKind,
+    label: String,
+    /// °C, RPM or volts depending on `kind`.
+    value: f32,
+    max: Option<f32>,
+    critical: Option<f32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SensorsSnapshot {
+    #[serde(flatten)]
+    tick: Tick,
+    sensors: Vec<SensorInfo>,
+}
+
+fn snapshot(source: &dyn MetricsSource, tick: Tick) -> SensorsSnapshot {
+    let sensors = source
+        .sensors()
+        .into_iter()
+        .map(|sensor| SensorInfo {
+            kind: sensor.kind,
+            label: sensor.label,
+            value: sensor.value,
+            max: sensor.max,
+            critical: sensor.critical,
+        })
+        .collect();
+    SensorsSnapshot { tick, sensors }
+}
+
+#[tauri::command]
+pub fn get_sensors_info(
+    registry: State<'_, StreamRegistry>,
+    hub: State<'_, Hub>,
+    window: Window,
+    on_event: Channel<SensorsSnapshot>,
+    token: String,
+    interval_ms: Option<u64>,
+) {
+    let stream = registry.begin(
+        StreamName::Sensors,
+        SubscriberId::new(&window, token),
+        interval_ms,
+    );
+    hub.subscribe(stream, on_event, snapshot);
+}
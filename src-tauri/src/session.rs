@@ -0,0 +1,499 @@
+//! Recording and replaying sessions for post-mortems and fixtures.
+//!
+//! A recording captures what the snapshot builders read rather than the
+//! snapshots themselves: on every tick that serves a live stream the
+//! collector appends a full [`MetricsSource`] frame to a JSON-lines file,
+//! so a replay runs at the rate the streams were recorded at. A frame only
+//! holds fresh readings for the subsystems some due stream reads; the rest
+//! keep whatever they last held. Processes are delta-encoded against the
+//! previous frame, since on a typical desktop most of them sit idle between
+//! frames.
+//!
+//! During a replay the collector hands a [`Replay`] to the builders
+//! instead of the live sources, so every stream (including the
+//! delta-encoded processes stream) works unchanged, at any speed.
+//! History and the metric store keep recording the live system; a session
+//! recording pauses.
+//!
+//! Sessions live in the app data dir's `sessions` directory; commands name
+//! them by file name only.
+
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Component, Path, PathBuf},
+    time::Instant,
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::{
+    cputime::CpuTimes,
+    error::{Error, Result},
+    history::unix_millis,
+    hub::Hub,
+    interrupts::InterruptReading,
+    pressure::{LoadAverage, PressureReading},
+    source::{
+        CpuReading, DiskReading, MemoryReading, MetricsSource, NetworkReading, ProcessReading,
+        SensorReading,
+    },
+};
+
+const VERSION: u32 = 1;
+
+/// First line of a session file.
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    version: u32,
+}
+
+/// Every following line.
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    /// Milliseconds since the recording started.
+    offset: u64,
+    /// Unix ms of the refresh behind this frame.
+    timestamp: u64,
+    global_cpu_usage: f32,
+    /// Missing from sessions recorded before the breakdown existed.
+    #[serde(default)]
+    global_cpu_times: Option<CpuTimes>,
+    cpus: Vec<CpuReading>,
+    memory: MemoryReading,
+    /// New processes and ones that changed since the previous entry.
+    processes: Vec<ProcessReading>,
+    /// PIDs gone since the previous entry.
+    exited: Vec<u32>,
+    networks: Vec<NetworkReading>,
+    disks: Vec<DiskReading>,
+    #[serde(default)]
+    sensors: Vec<SensorReading>,
+    #[serde(default)]
+    load_average: LoadAverage,
+    #[serde(default)]
+    pressure: PressureReading,
+    #[serde(default)]
+    interrupts: InterruptReading,
+}
+
+pub struct Recorder {
+    writer: BufWriter<File>,
+    started: Instant,
+    processes: HashMap<u32, ProcessReading>,
+}
+
+impl Recorder {
+    /// Fails rather than overwrite an existing session at `path`.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().write(true).create_new(true).open(path)?;
+        let mut writer = BufWriter::new(file);
+        let header = Header { version: VERSION };
+        serde_json::to_writer(&mut writer, &header)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+        Ok(Self {
+            writer,
+            started: Instant::now(),
+            processes: HashMap::new(),
+        })
+    }
+
+    pub fn write(&mut self, source: &dyn MetricsSource, timestamp: u64) -> io::Result<()> {
+        let current: HashMap<u32, ProcessReading> = source
+            .processes()
+            .into_iter()
+            .map(|process| (process.pid, process))
+            .collect();
+        let processes = current
+            .values()
+            .filter(|process| {
+                self.processes
+                    .get(&process.pid)
+                    .is_none_or(|old| old != *process)
+            })
+            .cloned()
+            .collect();
+        let exited = self
+            .processes
+            .keys()
+            .filter(|pid| !current.contains_key(pid))
+            .copied()
+            .collect();
+        self.processes = current;
+
+        let entry = Entry {
+            offset: u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX),
+            timestamp,
+            global_cpu_usage: source.global_cpu_usage(),
+            global_cpu_times: source.global_cpu_times(),
+            cpus: source.cpus(),
+            memory: source.memory(),
+            processes,
+            exited,
+            networks: source.networks(),
+            disks: source.disks(),
+            sensors: source.sensors(),
+            load_average: source.load_average(),
+            pressure: source.pressure(),
+            interrupts: source.interrupts(),
+        };
+        serde_json::to_writer(&mut self.writer, &entry)?;
+        self.writer.write_all(b"\n")?;
+        // Flush every frame: a post-mortem is no use if the crash eats it.
+        self.writer.flush()
+    }
+}
+
+/// A loaded session being played back against the wall clock.
+pub struct Replay {
+    entries: Vec<Entry>,
+    /// Entries before this one have been applied.
+    next: usize,
+    /// Recording offset (ms) that `resumed` corresponds to.
+    origin: u64,
+    resumed: Instant,
+    speed: f64,
+    state: ReplayState,
+}
+
+#[derive(Default)]
+struct ReplayState {
+    timestamp: u64,
+    global_cpu_usage: f32,
+    global_cpu_times: Option<CpuTimes>,
+    cpus: Vec<CpuReading>,
+    memory: MemoryReading,
+    processes: HashMap<u32, ProcessReading>,
+    networks: Vec<NetworkReading>,
+    disks: Vec<DiskReading>,
+    sensors: Vec<SensorReading>,
+    load_average: LoadAverage,
+    pressure: PressureReading,
+    interrupts: InterruptReading,
+}
+
+impl ReplayState {
+    fn apply(&mut self, entry: &Entry) {
+        self.timestamp = entry.timestamp;
+        self.global_cpu_usage = entry.global_cpu_usage;
+        self.global_cpu_times = entry.global_cpu_times;
+        self.cpus.clone_from(&entry.cpus);
+        self.memory = entry.memory.clone();
+        for pid in &entry.exited {
+            self.processes.remove(pid);
+        }
+        for process in &entry.processes {
+            self.processes.insert(process.pid, process.clone());
+        }
+        self.networks.clone_from(&entry.networks);
+        self.disks.clone_from(&entry.disks);
+        self.sensors.clone_from(&entry.sensors);
+        self.load_average = entry.load_average;
+        self.pressure = entry.pressure;
+        self.interrupts.clone_from(&entry.interrupts);
+    }
+}
+
+impl Replay {
+    /// Loads a whole session into memory. Reading stops quietly at a torn
+    /// last line, as left by a recording that was cut short.
+    pub fn open(path: &Path, speed: f64) -> io::Result<Self> {
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        let header: Header = match lines.next() {
+            Some(line) => serde_json::from_str(&line?)?,
+            None => return Err(io::Error::new(io::ErrorKind::InvalidData, "empty session")),
+        };
+        if header.version != VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported session version {}", header.version),
+            ));
+        }
+        let mut entries = Vec::new();
+        for line in lines {
+            match serde_json::from_str(&line?) {
+                Ok(entry) => entries.push(entry),
+                Err(err) if err.is_eof() => break,
+                Err(err) => return Err(err.into()),
+            }
+        }
+        if entries.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "session has no frames",
+            ));
+        }
+        let mut replay = Self {
+            entries,
+            next: 0,
+            origin: 0,
+            resumed: Instant::now(),
+            speed: clamp_speed(speed),
+            state: ReplayState::default(),
+        };
+        replay.advance(Instant::now());
+        Ok(replay)
+    }
+
+    /// Length of the recording in milliseconds.
+    pub fn duration(&self) -> u64 {
+        self.entries.last().map_or(0, |entry| entry.offset)
+    }
+
+    /// Applies every frame the replay clock has reached by `now`. The last
+    /// frame stays up once the recording runs out.
+    pub fn advance(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.resumed).as_secs_f64();
+        let position = self.origin + (elapsed * self.speed * 1000.0) as u64;
+        while let Some(entry) = self.entries.get(self.next) {
+            if entry.offset > position && self.next > 0 {
+                break;
+            }
+            self.state.apply(entry);
+            self.next += 1;
+        }
+    }
+
+    /// Jumps to `offset` ms into the recording. Processes are cumulative,
+    /// so going backwards rebuilds the state from the first frame.
+    pub fn seek(&mut self, offset: u64) {
+        let offset = offset.min(self.duration());
+        if self
+            .next
+            .checked_sub(1)
+            .is_some_and(|applied| self.entries[applied].offset > offset)
+        {
+            self.next = 0;
+            self.state = ReplayState::default();
+        }
+        self.origin = offset;
+        self.resumed = Instant::now();
+        self.advance(self.resumed);
+    }
+
+    /// Unix ms of the frame currently being served.
+    pub fn timestamp(&self) -> u64 {
+        self.state.timestamp
+    }
+}
+
+impl MetricsSource for Replay {
+    fn global_cpu_usage(&self) -> f32 {
+        self.state.global_cpu_usage
+    }
+
+    fn global_cpu_times(&self) -> Option<CpuTimes> {
+        self.state.global_cpu_times
+    }
+
+    fn cpus(&self) -> Vec<CpuReading> {
+        self.state.cpus.clone()
+    }
+
+    fn memory(&self) -> MemoryReading {
+        self.state.memory.clone()
+    }
+
+    fn processes(&self) -> Vec<ProcessReading> {
+        self.state.processes.values().cloned().collect()
+    }
+
+    fn process_count(&self) -> usize {
+        self.state.processes.len()
+    }
+
+    fn top_processes(&self, n: usize) -> Vec<ProcessReading> {
+        let mut processes: Vec<_> = self.state.processes.values().collect();
+        processes.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+        processes.into_iter().take(n).cloned().collect()
+    }
+
+    fn networks(&self) -> Vec<NetworkReading> {
+        self.state.networks.clone()
+    }
+
+    fn disks(&self) -> Vec<DiskReading> {
+        self.state.disks.clone()
+    }
+
+    fn sensors(&self) -> Vec<SensorReading> {
+        self.state.sensors.clone()
+    }
+
+    fn load_average(&self) -> LoadAverage {
+        self.state.load_average
+    }
+
+    fn pressure(&self) -> PressureReading {
+        self.state.pressure
+    }
+
+    fn interrupts(&self) -> InterruptReading {
+        self.state.interrupts.clone()
+    }
+}
+
+/// Anything from a crawl to skimming an hour in a few seconds.
+fn clamp_speed(speed: f64) -> f64 {
+    if speed.is_finite() {
+        speed.clamp(0.1, 1000.0)
+    } else {
+        1.0
+    }
+}
+
+fn sessions_dir(app: &AppHandle) -> Result<PathBuf> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(io::Error::other)?
+        .join("sessions");
+    fs::create_dir_all(&dir).map_err(|err| Error::io_at(&dir, err))?;
+    Ok(dir)
+}
+
+/// `name` directly under `dir`. Anything that could point elsewhere (an
+/// absolute path, a separator, `..`) is refused.
+fn session_path(dir: &Path, name: &str) -> Result<PathBuf> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(file)), None) if file == name => Ok(dir.join(file)),
+        _ => Err(Error::io_at(
+            name,
+            io::Error::new(io::ErrorKind::InvalidInput, "not a session file name"),
+        )),
+    }
+}
+
+/// Starts recording the running streams to the session file `name`, or to
+/// a new, timestamped one. Returns the file name actually used; an existing
+/// session is never overwritten.
+#[tauri::command]
+pub fn start_recording(
+    app: AppHandle,
+    hub: State<'_, Hub>,
+    name: Option<String>,
+) -> Result<String> {
+    let name = name.unwrap_or_else(|| format!("session-{}.jsonl", unix_millis()));
+    let path = session_path(&sessions_dir(&app)?, &name)?;
+    let recorder = Recorder::create(&path).map_err(|err| Error::io_at(&path, err))?;
+    hub.record(Some(recorder));
+    Ok(name)
+}
+
+#[tauri::command]
+pub fn stop_recording(hub: State<'_, Hub>) {
+    hub.record(None);
+}
+
+/// Feeds every stream from the session file `name` instead of the live
+/// system, `speed` times faster than real time (default 1). Returns the
+/// recording's length in milliseconds.
+#[tauri::command]
+pub async fn start_replay(
+    app: AppHandle,
+    hub: State<'_, Hub>,
+    name: String,
+    speed: Option<f64>,
+) -> Result<u64> {
+    let path = session_path(&sessions_dir(&app)?, &name)?;
+    let speed = speed.unwrap_or(1.0);
+    // Loading reads and parses the whole file.
+    let replay = tauri::async_runtime::spawn_blocking(move || {
+        Replay::open(&path, speed).map_err(|err| Error::io_at(&path, err))
+    })
+    .await
+    .map_err(io::Error::other)??;
+    let duration = replay.duration();
+    hub.replay(Some(replay));
+    Ok(duration)
+}
+
+#[tauri::command]
+pub fn stop_replay(hub: State<'_, Hub>) {
+    hub.replay(None);
+}
+
+/// Jumps the running replay to `offset_ms` into the recording.
+#[tauri::command]
+pub fn seek_replay(hub: State<'_, Hub>, offset_ms: u64) {
+    hub.seek_replay(offset_ms);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        source::scripted::{Frame, ScriptedSource},
+        sysfs::Fixture,
+    };
+
+    fn process(pid: u32, start_time: u64, cpu_usage: f32) -> ProcessReading {
+        ProcessReading {
+            pid,
+            name: format!("proc{pid}"),
+            start_time,
+            cpu_usage,
+            ..ProcessReading::default()
+        }
+    }
+
+    #[test]
+    fn replay_reproduces_recorded_processes() {
+        let fixture = Fixture::new("session");
+        let path = fixture.root().join("session.jsonl");
+        let mut source = ScriptedSource::new(vec![
+            Frame {
+                processes: vec![process(1, 900, 0.0), process(2, 995, 10.0)],
+                ..Frame::default()
+            },
+            Frame {
+                processes: vec![process(1, 900, 0.0), process(3, 1001, 50.0)],
+                ..Frame::default()
+            },
+        ]);
+
+        let mut recorder = Recorder::create(&path).expect("create session");
+        recorder.write(&source, 1_000_000).expect("write frame");
+        source.advance();
+        recorder.write(&source, 1_001_000).expect("write frame");
+        drop(recorder);
+        assert!(
+            Recorder::create(&path).is_err(),
+            "an existing session is kept"
+        );
+
+        let mut replay = Replay::open(&path, 1.0).expect("open session");
+        replay.seek(replay.duration());
+
+        let mut processes = replay.processes();
+        processes.sort_by_key(|process| process.pid);
+        let mut expected = source.processes();
+        expected.sort_by_key(|process| process.pid);
+        assert_eq!(processes, expected);
+        assert_eq!(replay.timestamp(), 1_001_000);
+    }
+
+    #[test]
+    fn session_names_stay_in_the_sessions_dir() {
+        let dir = Path::new("sessions");
+        assert_eq!(
+            session_path(dir, "a.jsonl").expect("plain name"),
+            dir.join("a.jsonl")
+        );
+        for name in [
+            "",
+            ".",
+            "..",
+            "../a.jsonl",
+            "a/b.jsonl",
+            "a/",
+            "/tmp/a.jsonl",
+        ] {
+            assert!(session_path(dir, name).is_err(), "{name:?} was accepted");
+        }
+    }
+}
@@ -0,0 +1,303 @@
+//! What the snapshot builders read, decoupled from sysinfo.
+//!
+//! Builders take any [`MetricsSource`] rather than the collector's
+//! [`Sources`] directly, so the same code runs against live sysinfo data in
+//! the app and against [`scripted::ScriptedSource`] in tests. Readings are
+//! plain owned values with rates already converted to bytes per second.
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{DiskKind, Pid, Process};
+
+use crate::{
+    cpufreq::FrequencyScaling,
+    cpuid::CoreType,
+    cpuidle::IdleResidency,
+    cputime::CpuTimes,
+    hub::Sources,
+    interrupts::InterruptReading,
+    pressure::{LoadAverage, PressureReading},
+    topology,
+};
+
+#[cfg(test)]
+pub mod scripted;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuReading {
+    pub name: String,
+    /// Percent.
+    pub usage: f32,
+    /// MHz.
+    pub frequency: u64,
+    /// `None` where the platform doesn't expose it, and on the first refresh.
+    #[serde(default)]
+    pub times: Option<CpuTimes>,
+    /// Linux only.
+    #[serde(default)]
+    pub scaling: Option<FrequencyScaling>,
+    /// Only set on hybrid parts.
+    #[serde(default)]
+    pub core_type: Option<CoreType>,
+    /// See [`topology::CoreClasses::frequency_class`].
+    #[serde(default)]
+    pub frequency_class: Option<u32>,
+    /// Empty unless a subscriber asked for idle states; Linux only.
+    #[serde(default)]
+    pub idle_states: Vec<IdleResidency>,
+}
+
+/// All values in bytes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryReading {
+    pub total: u64,
+    pub used: u64,
+    pub free: u64,
+    pub available: u64,
+    pub swap_total: u64,
+    pub swap_used: u64,
+    pub swap_free: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessReading {
+    pub pid: u32,
+    pub name: String,
+    pub cmd: Vec<String>,
+    pub exe: Option<String>,
+    /// Percent of one core.
+    pub cpu_usage: f32,
+    pub memory: u64,
+    pub virtual_memory: u64,
+    /// Unix seconds. Missing (0) from sessions recorded before it was kept.
+    #[serde(default)]
+    pub start_time: u64,
+    pub parent: Option<u32>,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetworkReading {
+    pub name: String,
+    /// Bytes per second since the previous refresh.
+    pub received_rate: u64,
+    pub transmitted_rate: u64,
+    pub total_received: u64,
+    pub total_transmitted: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiskReading {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    /// "HDD", "SSD" or "Unknown".
+    pub kind: String,
+    pub total_space: u64,
+    pub available_space: u64,
+    pub removable: bool,
+    /// Bytes per second since the previous refresh.
+    pub read_rate: u64,
+    pub write_rate: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensorKind {
+    /// Degrees Celsius.
+    Temperature,
+    /// RPM.
+    Fan,
+    /// Volts.
+    Voltage,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SensorReading {
+    pub kind: SensorKind,
+    pub label: String,
+    /// In the kind's unit.
+    pub value: f32,
+    pub max: Option<f32>,
+    pub critical: Option<f32>,
+}
+
+/// A refreshed view of the system. Implementations only hand out what they
+/// already hold; refreshing is the collector's job.
+pub trait MetricsSource {
+    /// Percent, averaged over all cores.
+    fn global_cpu_usage(&self) -> f32;
+    /// All cores together; see [`CpuReading::times`].
+    fn global_cpu_times(&self) -> Option<CpuTimes>;
+    fn cpus(&self) -> Vec<CpuReading>;
+    fn memory(&self) -> MemoryReading;
+    fn processes(&self) -> Vec<ProcessReading>;
+    fn process_count(&self) -> usize {
+        self.processes().len()
+    }
+    /// The `n` processes using the most CPU, busiest first. Implementations
+    /// holding many processes should pick these before building readings.
+    fn top_processes(&self, n: usize) -> Vec<ProcessReading> {
+        let mut processes = self.processes();
+        processes.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+        processes.truncate(n);
+        processes
+    }
+    fn networks(&self) -> Vec<NetworkReading>;
+    fn disks(&self) -> Vec<DiskReading>;
+    fn sensors(&self) -> Vec<SensorReading>;
+    fn load_average(&self) -> LoadAverage;
+    fn pressure(&self) -> PressureReading;
+    fn interrupts(&self) -> InterruptReading;
+}
+
+fn process_reading(pid: Pid, process: &Process) -> ProcessReading {
+    ProcessReading {
+        pid: pid.as_u32(),
+        name: process.name().to_string_lossy().into_owned(),
+        cmd: process
+            .cmd()
+            .iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect(),
+        exe: process
+            .exe()
+            .map(|path| path.to_string_lossy().into_owned()),
+        cpu_usage: process.cpu_usage(),
+        memory: process.memory(),
+        virtual_memory: process.virtual_memory(),
+        start_time: process.start_time(),
+        parent: process.parent().map(|parent| parent.as_u32()),
+        status: process.status().to_string(),
+    }
+}
+
+impl MetricsSource for Sources {
+    fn global_cpu_usage(&self) -> f32 {
+        self.system.global_cpu_usage()
+    }
+
+    fn global_cpu_times(&self) -> Option<CpuTimes> {
+        self.cpu_times.global()
+    }
+
+    fn cpus(&self) -> Vec<CpuReading> {
+        self.system
+            .cpus()
+            .iter()
+            .map(|cpu| {
+                let id = topology::cpu_id(cpu.name());
+                CpuReading {
+                    name: cpu.name().to_string(),
+                    usage: cpu.cpu_usage(),
+                    frequency: cpu.frequency(),
+                    times: self.cpu_times.core(cpu.name()),
+                    scaling: self.cpufreq.core(cpu.name()),
+                    core_type: id.and_then(|id| self.core_classes.core_type(id)),
+                    frequency_class: id.and_then(|id| self.core_classes.frequency_class(id)),
+                    idle_states: self.cpuidle.core(cpu.name()),
+                }
+            })
+            .collect()
+    }
+
+    fn memory(&self) -> MemoryReading {
+        let sys = &self.system;
+        MemoryReading {
+            total: sys.total_memory(),
+            used: sys.used_memory(),
+            free: sys.free_memory(),
+            available: sys.available_memory(),
+            swap_total: sys.total_swap(),
+            swap_used: sys.used_swap(),
+            swap_free: sys.free_swap(),
+        }
+    }
+
+    fn processes(&self) -> Vec<ProcessReading> {
+        self.processes
+            .processes()
+            .iter()
+            .map(|(pid, process)| process_reading(*pid, process))
+            .collect()
+    }
+
+    fn process_count(&self) -> usize {
+        self.processes.processes().len()
+    }
+
+    fn top_processes(&self, n: usize) -> Vec<ProcessReading> {
+        let mut processes: Vec<_> = self.processes.processes().iter().collect();
+        processes.sort_by(|(_, a), (_, b)| b.cpu_usage().total_cmp(&a.cpu_usage()));
+        processes
+            .into_iter()
+            .take(n)
+            .map(|(pid, process)| process_reading(*pid, process))
+            .collect()
+    }
+
+    fn networks(&self) -> Vec<NetworkReading> {
+        self.networks
+            .iter()
+            .map(|(name, data)| NetworkReading {
+                name: name.clone(),
+                received_rate: self.network_rate(data.received()),
+                transmitted_rate: self.network_rate(data.transmitted()),
+                total_received: data.total_received(),
+                total_transmitted: data.total_transmitted(),
+            })
+            .collect()
+    }
+
+    fn disks(&self) -> Vec<DiskReading> {
+        self.disks
+            .iter()
+            .map(|disk| {
+                let usage = disk.usage();
+                DiskReading {
+                    name: disk.name().to_string_lossy().into_owned(),
+                    mount_point: disk.mount_point().to_string_lossy().into_owned(),
+                    file_system: disk.file_system().to_string_lossy().into_owned(),
+                    kind: match disk.kind() {
+                        DiskKind::HDD => "HDD".to_string(),
+                        DiskKind::SSD => "SSD".to_string(),
+                        DiskKind::Unknown(_) => "Unknown".to_string(),
+                    },
+                    total_space: disk.total_space(),
+                    available_space: disk.available_space(),
+                    removable: disk.is_removable(),
+                    read_rate: self.disk_rate(usage.read_bytes),
+                    write_rate: self.disk_rate(usage.written_bytes),
+                }
+            })
+            .collect()
+    }
+
+    fn sensors(&self) -> Vec<SensorReading> {
+        // Empty on Linux, where `hwmon` reads temperatures too.
+        let temperatures = self.components.iter().filter_map(|component| {
+            Some(SensorReading {
+                kind: SensorKind::Temperature,
+                label: component.label().to_string(),
+                // NaN when the read failed.
+                value: component.temperature().filter(|value| !value.is_nan())?,
+                max: component.max().filter(|value| !value.is_nan()),
+                critical: component.critical(),
+            })
+        });
+        temperatures
+            .chain(self.hwmon.readings().iter().cloned())
+            .collect()
+    }
+
+    fn load_average(&self) -> LoadAverage {
+        self.pressure.load_average()
+    }
+
+    fn pressure(&self) -> PressureReading {
+        self.pressure.pressure()
+    }
+
+    fn interrupts(&self) -> InterruptReading {
+        self.interrupts.reading().clone()
+    }
+}
@@ -0,0 +1,103 @@
+//! A [`MetricsSource`] that plays back a fixed script, for tests.
+
+use super::{
+    CpuReading, CpuTimes, DiskReading, MemoryReading, MetricsSource, NetworkReading,
+    ProcessReading, SensorReading,
+};
+use crate::{
+    interrupts::InterruptReading,
+    pressure::{LoadAverage, PressureReading},
+};
+
+/// Everything the source reports at one step of the script.
+#[derive(Debug, Clone, Default)]
+pub struct Frame {
+    pub global_cpu_usage: f32,
+    pub global_cpu_times: Option<CpuTimes>,
+    pub cpus: Vec<CpuReading>,
+    pub memory: MemoryReading,
+    pub processes: Vec<ProcessReading>,
+    pub networks: Vec<NetworkReading>,
+    pub disks: Vec<DiskReading>,
+    pub sensors: Vec<SensorReading>,
+    pub load_average: LoadAverage,
+    pub pressure: PressureReading,
+    pub interrupts: InterruptReading,
+}
+
+/// Reports one [`Frame`] until [`advance`](Self::advance) moves on to the
+/// next; the last frame repeats once the script runs out.
+pub struct ScriptedSource {
+    frames: Vec<Frame>,
+    position: usize,
+}
+
+impl ScriptedSource {
+    pub fn new(frames: Vec<Frame>) -> Self {
+        assert!(!frames.is_empty(), "a script needs at least one frame");
+        Self {
+            frames,
+            position: 0,
+        }
+    }
+
+    pub fn advance(&mut self) {
+        self.position = (self.position + 1).min(self.frames.len() - 1);
+    }
+
+    fn frame(&self) -> &Frame {
+        &self.frames[self.position]
+    }
+}
+
+impl From<Frame> for ScriptedSource {
+    fn from(frame: Frame) -> Self {
+        Self::new(vec![frame])
+    }
+}
+
+impl MetricsSource for ScriptedSource {
+    fn global_cpu_usage(&self) -> f32 {
+        self.frame().global_cpu_usage
+    }
+
+    fn global_cpu_times(&self) -> Option<CpuTimes> {
+        self.frame().global_cpu_times
+    }
+
+    fn cpus(&self) -> Vec<CpuReading> {
+        self.frame().cpus.clone()
+    }
+
+    fn memory(&self) -> MemoryReading {
+        self.frame().memory.clone()
+    }
+
+    fn processes(&self) -> Vec<ProcessReading> {
+        self.frame().processes.clone()
+    }
+
+    fn networks(&self) -> Vec<NetworkReading> {
+        self.frame().networks.clone()
+    }
+
+    fn disks(&self) -> Vec<DiskReading> {
+        self.frame().disks.clone()
+    }
+
+    fn sensors(&self) -> Vec<SensorReading> {
+        self.frame().sensors.clone()
+    }
+
+    fn load_average(&self) -> LoadAverage {
+        self.frame().load_average
+    }
+
+    fn pressure(&self) -> PressureReading {
+        self.frame().pressure
+    }
+
+    fn interrupts(&self) -> InterruptReading {
+        self.frame().interrupts.clone()
+    }
+}
@@ -0,0 +1,667 @@
+//! Persistent, downsampled metric store in the app data directory.
+//!
+//! Each [`Resolution`] tier is an append-only log of fixed-size records,
+//! so a query can binary-search its way to the start of its range:
+//!
+//! ```text
+//! timestamp: u64 LE (unix ms) | value: f64 LE | metric: u8 | name_len: u8 | name, zero-padded
+//! ```
+//!
+//! The collector appends the once-a-second history samples to every tier,
+//! plus the [`STORED_PROCESSES`] busiest processes every
+//! [`PROCESS_SAMPLE_INTERVAL`], and each tier averages them into buckets
+//! of its own width. A process only counts towards the buckets it was
+//! among the busiest in. A bucket is written once the clock has moved past
+//! it, so records are in timestamp order unless the wall clock steps back;
+//! compaction sorts them again.
+//!
+//! A tier is compacted (rewritten without expired records) when it
+//! outgrows its size cap or a quarter of its retention has passed, and
+//! after the first append since opening. That happens on a thread of its
+//! own, so neither the collector nor a query waits for it; queries read the
+//! file through their own handle and never take the tiers' lock.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use serde::Deserialize;
+use tauri::State;
+
+use crate::{
+    error::{Error, Result},
+    history::{HistoryPoint, HistorySeries, Metric, Sample},
+};
+
+const SECOND: u64 = 1000;
+const MINUTE: u64 = 60 * SECOND;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+const MIB: u64 = 1024 * 1024;
+
+/// Refreshing every process is too much work to do every second just for
+/// the store.
+pub const PROCESS_SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+/// Process names persisted per sample.
+pub const STORED_PROCESSES: usize = 10;
+
+/// Bytes per record.
+const RECORD_LEN: u64 = 64;
+/// Bytes left for the name after the fixed fields.
+const NAME_CAPACITY: usize = RECORD_LEN as usize - 18;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resolution {
+    Second,
+    Minute,
+    FifteenMinutes,
+}
+
+impl Resolution {
+    const ALL: [Self; 3] = [Self::Second, Self::Minute, Self::FifteenMinutes];
+
+    /// Position in [`ALL`](Self::ALL), and so in the store's tiers.
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::Second => "1s.log",
+            Self::Minute => "1m.log",
+            Self::FifteenMinutes => "15m.log",
+        }
+    }
+
+    /// Bucket width in milliseconds.
+    fn width(self) -> u64 {
+        match self {
+            Self::Second => SECOND,
+            Self::Minute => MINUTE,
+            Self::FifteenMinutes => 15 * MINUTE,
+        }
+    }
+
+    fn retention(self) -> u64 {
+        match self {
+            Self::Second => HOUR,
+            Self::Minute => 7 * DAY,
+            Self::FifteenMinutes => 365 * DAY,
+        }
+    }
+
+    fn size_cap(self) -> u64 {
+        match self {
+            Self::Second => 16 * MIB,
+            Self::Minute => 32 * MIB,
+            Self::FifteenMinutes => 64 * MIB,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Record {
+    timestamp: u64,
+    metric: Metric,
+    name: String,
+    value: f64,
+}
+
+impl Record {
+    fn encode(&self) -> [u8; RECORD_LEN as usize] {
+        let name = truncate_name(&self.name);
+        let mut bytes = [0u8; RECORD_LEN as usize];
+        bytes[..8].copy_from_slice(&self.timestamp.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.value.to_le_bytes());
+        bytes[16] = self.metric.code();
+        bytes[17] = name.len() as u8;
+        bytes[18..18 + name.len()].copy_from_slice(name.as_bytes());
+        bytes
+    }
+
+    /// `None` for a corrupt record. Records are fixed-size, so the ones
+    /// after it are still readable.
+    fn decode(bytes: &[u8; RECORD_LEN as usize]) -> Option<Self> {
+        let mut timestamp = [0u8; 8];
+        timestamp.copy_from_slice(&bytes[..8]);
+        let mut value = [0u8; 8];
+        value.copy_from_slice(&bytes[8..16]);
+        let name = bytes[18..].get(..usize::from(bytes[17]))?;
+        Some(Self {
+            timestamp: u64::from_le_bytes(timestamp),
+            metric: Metric::from_code(bytes[16])?,
+            name: String::from_utf8_lossy(name).into_owned(),
+            value: f64::from_le_bytes(value),
+        })
+    }
+}
+
+/// Cuts `name` on a char boundary to fit a record.
+fn truncate_name(name: &str) -> &str {
+    let mut end = name.len().min(NAME_CAPACITY);
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+    &name[..end]
+}
+
+/// Whole records in `file`; a torn one at the end doesn't count.
+fn record_count(file: &File) -> io::Result<u64> {
+    Ok(file.metadata()?.len() / RECORD_LEN)
+}
+
+fn read_timestamp(file: &mut File, index: u64) -> io::Result<u64> {
+    let mut timestamp = [0u8; 8];
+    file.seek(SeekFrom::Start(index * RECORD_LEN))?;
+    file.read_exact(&mut timestamp)?;
+    Ok(u64::from_le_bytes(timestamp))
+}
+
+/// Index of the first of `count` records at or after `from`.
+fn first_at_or_after(file: &mut File, count: u64, from: u64) -> io::Result<u64> {
+    let (mut low, mut high) = (0, count);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if read_timestamp(file, middle)? < from {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    Ok(low)
+}
+
+/// Reads the records from index `start` up to (not including) `end`,
+/// skipping corrupt ones.
+struct Records {
+    input: BufReader<File>,
+    remaining: u64,
+}
+
+impl Records {
+    fn new(mut file: File, start: u64, end: u64) -> io::Result<Self> {
+        file.seek(SeekFrom::Start(start * RECORD_LEN))?;
+        Ok(Self {
+            input: BufReader::new(file),
+            remaining: end.saturating_sub(start),
+        })
+    }
+}
+
+impl Iterator for Records {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining > 0 {
+            self.remaining -= 1;
+            let mut bytes = [0u8; RECORD_LEN as usize];
+            if let Err(err) = self.input.read_exact(&mut bytes) {
+                self.remaining = 0;
+                return Some(Err(err));
+            }
+            if let Some(record) = Record::decode(&bytes) {
+                return Some(Ok(record));
+            }
+        }
+        None
+    }
+}
+
+#[derive(Default)]
+struct Bucket {
+    start: u64,
+    sum: f64,
+    count: u32,
+}
+
+struct Tier {
+    resolution: Resolution,
+    path: PathBuf,
+    writer: BufWriter<File>,
+    bytes: u64,
+    size_cap: u64,
+    /// Unix ms after which compaction is due even below the size cap.
+    compact_at: u64,
+    /// No compaction is started before this, after one failed.
+    retry_at: u64,
+    compacting: bool,
+    /// Running averages for the current bucket of each series.
+    buckets: HashMap<(Metric, String), Bucket>,
+}
+
+impl Tier {
+    fn open(dir: &Path, resolution: Resolution) -> io::Result<Self> {
+        let path = dir.join(resolution.file_name());
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let len = file.metadata()?.len();
+        let bytes = len - len % RECORD_LEN;
+        if bytes != len {
+            // Torn by a crash mid-write; later appends must stay aligned.
+            file.set_len(bytes)?;
+        }
+        Ok(Self {
+            resolution,
+            path,
+            writer: BufWriter::new(file),
+            bytes,
+            size_cap: resolution.size_cap(),
+            compact_at: 0,
+            retry_at: 0,
+            compacting: false,
+            buckets: HashMap::new(),
+        })
+    }
+
+    fn append(&mut self, timestamp: u64, samples: &[Sample]) -> io::Result<()> {
+        let width = self.resolution.width();
+        let start = timestamp - timestamp % width;
+        // Close every bucket the clock has moved past, including those of
+        // series that stopped reporting (unplugged disk, downed interface).
+        let mut closed = Vec::new();
+        self.buckets.retain(|(metric, name), bucket| {
+            if bucket.start == start {
+                return true;
+            }
+            closed.push(Record {
+                timestamp: bucket.start,
+                metric: *metric,
+                name: name.clone(),
+                value: bucket.sum / f64::from(bucket.count),
+            });
+            false
+        });
+        closed.sort_by_key(|record| record.timestamp);
+        for record in &closed {
+            self.writer.write_all(&record.encode())?;
+            self.bytes += RECORD_LEN;
+        }
+        self.writer.flush()?;
+
+        for sample in samples {
+            let bucket = self
+                .buckets
+                .entry((sample.metric, sample.name.clone()))
+                .or_insert_with(|| Bucket {
+                    start,
+                    ..Bucket::default()
+                });
+            bucket.sum += sample.value;
+            bucket.count += 1;
+        }
+        Ok(())
+    }
+
+    fn needs_compaction(&self, now: u64) -> bool {
+        !self.compacting
+            && now >= self.retry_at
+            && (self.bytes > self.size_cap || now >= self.compact_at)
+    }
+}
+
+/// Shared between the collector (writer), compaction and [`query_metrics`]
+/// (reader).
+#[derive(Clone)]
+pub struct Store {
+    dir: Arc<Path>,
+    tiers: Arc<Mutex<Vec<Tier>>>,
+}
+
+impl Store {
+    pub fn open(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let tiers = Resolution::ALL
+            .into_iter()
+            .map(|resolution| Tier::open(dir, resolution))
+            .collect::<io::Result<_>>()?;
+        Ok(Self {
+            dir: Arc::from(dir),
+            tiers: Arc::new(Mutex::new(tiers)),
+        })
+    }
+
+    fn path(&self, resolution: Resolution) -> PathBuf {
+        self.dir.join(resolution.file_name())
+    }
+
+    /// Appends to every tier and starts compacting any that need it. Only
+    /// a failed write is an error.
+    pub fn append(&self, timestamp: u64, samples: &[Sample]) -> io::Result<()> {
+        let mut due = Vec::new();
+        for tier in self.lock().iter_mut() {
+            tier.append(timestamp, samples)?;
+            if tier.needs_compaction(timestamp) {
+                tier.compacting = true;
+                due.push(tier.resolution);
+            }
+        }
+        for resolution in due {
+            let store = self.clone();
+            let spawned = thread::Builder::new()
+                .name("metric-compaction".to_string())
+                .spawn(move || store.compact(resolution, timestamp));
+            // Compaction can wait; appending shouldn't stop over it.
+            if let Err(err) = spawned {
+                log::warn!("starting metric compaction: {err}");
+                let mut tiers = self.lock();
+                let tier = &mut tiers[resolution.index()];
+                tier.compacting = false;
+                tier.retry_at = timestamp + MINUTE;
+            }
+        }
+        Ok(())
+    }
+
+    fn compact(&self, resolution: Resolution, now: u64) {
+        let result = self.rewrite(resolution, now);
+        let mut tiers = self.lock();
+        let tier = &mut tiers[resolution.index()];
+        tier.compacting = false;
+        match result {
+            Ok(()) => tier.compact_at = now + resolution.retention() / 4,
+            Err(err) => {
+                log::warn!("compacting {}: {err}", tier.path.display());
+                tier.retry_at = now + MINUTE;
+            }
+        }
+    }
+
+    /// Rewrites a tier keeping only records inside the retention window,
+    /// sorted, and trimmed from the oldest end to three quarters of its
+    /// size cap. The bulk of the work is done on the records present when
+    /// it starts; only the ones appended since are copied over under the
+    /// lock, right before the new file replaces the old one.
+    fn rewrite(&self, resolution: Resolution, now: u64) -> io::Result<()> {
+        let path = self.path(resolution);
+        let size_cap = self.lock()[resolution.index()].size_cap;
+
+        let file = File::open(&path)?;
+        let snapshot = record_count(&file)?;
+        let horizon = now.saturating_sub(resolution.retention());
+        let mut records = Vec::new();
+        for record in Records::new(file, 0, snapshot)? {
+            let record = record?;
+            if record.timestamp >= horizon {
+                records.push(record);
+            }
+        }
+        records.sort_by_key(|record| record.timestamp);
+        let budget = usize::try_from(size_cap / 4 * 3 / RECORD_LEN).unwrap_or(usize::MAX);
+        let skip = records.len().saturating_sub(budget);
+
+        let temp = path.with_extension("tmp");
+        let mut out = BufWriter::new(File::create(&temp)?);
+        for record in &records[skip..] {
+            out.write_all(&record.encode())?;
+        }
+
+        let mut tiers = self.lock();
+        let tier = &mut tiers[resolution.index()];
+        tier.writer.flush()?;
+        let file = File::open(&path)?;
+        let end = record_count(&file)?;
+        for record in Records::new(file, snapshot, end)? {
+            out.write_all(&record?.encode())?;
+        }
+        out.into_inner().map_err(io::IntoInnerError::into_error)?;
+        fs::rename(&temp, &path)?;
+        tier.writer = BufWriter::new(OpenOptions::new().append(true).open(&path)?);
+        tier.bytes = fs::metadata(&path)?.len();
+        Ok(())
+    }
+
+    /// Reads the tier file through its own handle, so it never waits on
+    /// the collector or a compaction.
+    pub fn query(
+        &self,
+        metric: Metric,
+        from: u64,
+        to: u64,
+        resolution: Resolution,
+    ) -> Result<Vec<HistorySeries>> {
+        let path = self.path(resolution);
+        query_file(&path, metric, from, to).map_err(|err| Error::io_at(&path, err))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Tier>> {
+        // A panic mid-append at worst leaves a torn record, which readers
+        // already ignore.
+        self.tiers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn query_file(path: &Path, metric: Metric, from: u64, to: u64) -> io::Result<Vec<HistorySeries>> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let count = record_count(&file)?;
+    let start = first_at_or_after(&mut file, count, from)?;
+    let mut series: BTreeMap<String, Vec<HistoryPoint>> = BTreeMap::new();
+    for record in Records::new(file, start, count)? {
+        let record = record?;
+        if record.timestamp > to {
+            break;
+        }
+        if record.metric == metric {
+            series.entry(record.name).or_default().push(HistoryPoint {
+                timestamp: record.timestamp,
+                value: record.value,
+            });
+        }
+    }
+    Ok(series
+        .into_iter()
+        .map(|(name, points)| HistorySeries { name, points })
+        .collect())
+}
+
+/// Points of `metric` between `from` and `to` (unix ms, inclusive) at the
+/// given resolution, one series per name. `store` is `None` when it
+/// couldn't be opened at startup.
+#[tauri::command]
+pub async fn query_metrics(
+    store: State<'_, Option<Store>>,
+    metric: Metric,
+    from: u64,
+    to: u64,
+    resolution: Resolution,
+) -> Result<Vec<HistorySeries>> {
+    let Some(store) = store.inner().clone() else {
+        return Err(Error::from(io::Error::other(
+            "The metric store could not be opened",
+        )));
+    };
+    tauri::async_runtime::spawn_blocking(move || store.query(metric, from, to, resolution))
+        .await
+        .map_err(io::Error::other)?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::Fixture;
+
+    /// A multiple of every tier's width.
+    const BASE: u64 = 1_800_000_000_000;
+
+    fn sample(metric: Metric, name: &str, value: f64) -> Sample {
+        Sample {
+            metric,
+            name: name.to_string(),
+            value,
+        }
+    }
+
+    /// Appends without starting a background compaction.
+    fn append(store: &Store, timestamp: u64, samples: &[Sample]) {
+        for tier in store.lock().iter_mut() {
+            tier.append(timestamp, samples).expect("append");
+        }
+    }
+
+    fn values(series: &[HistorySeries]) -> Vec<(u64, f64)> {
+        series[0]
+            .points
+            .iter()
+            .map(|point| ((point.timestamp - BASE) / SECOND, point.value))
+            .collect()
+    }
+
+    #[test]
+    fn records_round_trip() {
+        let record = Record {
+            timestamp: BASE,
+            metric: Metric::DiskRead,
+            name: "/".to_string(),
+            value: 1234.5,
+        };
+        assert_eq!(Record::decode(&record.encode()), Some(record));
+
+        let long = Record {
+            timestamp: BASE,
+            metric: Metric::NetworkRx,
+            name: "é".repeat(NAME_CAPACITY),
+            value: 0.0,
+        };
+        let decoded = Record::decode(&long.encode()).expect("decode");
+        assert_eq!(decoded.name, "é".repeat(NAME_CAPACITY / 2));
+
+        let mut corrupt = long.encode();
+        corrupt[16] = u8::MAX;
+        assert_eq!(Record::decode(&corrupt), None);
+    }
+
+    #[test]
+    fn queries_seek_to_the_requested_range() {
+        let fixture = Fixture::new("store-range");
+        let store = Store::open(fixture.root()).expect("open store");
+        for second in 0..=100 {
+            append(
+                &store,
+                BASE + second * SECOND,
+                &[
+                    sample(Metric::Cpu, "cpu", second as f64),
+                    sample(Metric::Memory, "memory", 50.0),
+                ],
+            );
+        }
+
+        let series = store
+            .query(
+                Metric::Cpu,
+                BASE + 40 * SECOND,
+                BASE + 42 * SECOND,
+                Resolution::Second,
+            )
+            .expect("query");
+        assert_eq!(series.len(), 1);
+        assert_eq!(values(&series), [(40, 40.0), (41, 41.0), (42, 42.0)]);
+    }
+
+    #[test]
+    fn ignores_a_torn_trailing_record() {
+        let fixture = Fixture::new("store-torn");
+        let store = Store::open(fixture.root()).expect("open store");
+        for second in 0..3 {
+            append(
+                &store,
+                BASE + second * SECOND,
+                &[sample(Metric::Cpu, "cpu", 1.0)],
+            );
+        }
+        drop(store);
+        let path = fixture.root().join(Resolution::Second.file_name());
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("open tier");
+        file.write_all(&[0xff; 10]).expect("tear");
+
+        let store = Store::open(fixture.root()).expect("reopen store");
+        assert_eq!(
+            fs::metadata(&path).expect("stat tier").len(),
+            2 * RECORD_LEN
+        );
+        append(
+            &store,
+            BASE + 10 * SECOND,
+            &[sample(Metric::Cpu, "cpu", 5.0)],
+        );
+        append(&store, BASE + 11 * SECOND, &[]);
+        let series = store
+            .query(Metric::Cpu, 0, u64::MAX, Resolution::Second)
+            .expect("query");
+        assert_eq!(values(&series), [(0, 1.0), (1, 1.0), (10, 5.0)]);
+    }
+
+    #[test]
+    fn downsamples_into_coarser_tiers() {
+        let fixture = Fixture::new("store-downsample");
+        let store = Store::open(fixture.root()).expect("open store");
+        // One minute at 10, one at 20, then one sample to close the second.
+        for second in 0..=120 {
+            let value = if second < 60 { 10.0 } else { 20.0 };
+            append(
+                &store,
+                BASE + second * SECOND,
+                &[sample(Metric::Cpu, "cpu", value)],
+            );
+        }
+
+        let minutes = store
+            .query(Metric::Cpu, 0, u64::MAX, Resolution::Minute)
+            .expect("query");
+        assert_eq!(values(&minutes), [(0, 10.0), (60, 20.0)]);
+        let quarters = store
+            .query(Metric::Cpu, 0, u64::MAX, Resolution::FifteenMinutes)
+            .expect("query");
+        assert!(quarters.is_empty(), "the first quarter hour is still open");
+    }
+
+    #[test]
+    fn compaction_trims_to_the_size_cap() {
+        let fixture = Fixture::new("store-compaction");
+        let store = Store::open(fixture.root()).expect("open store");
+        {
+            let tier = &mut store.lock()[Resolution::Second.index()];
+            tier.size_cap = 20 * RECORD_LEN;
+            // Only the size cap should trigger this one.
+            tier.compact_at = u64::MAX;
+        }
+        for second in 0..=30 {
+            append(
+                &store,
+                BASE + second * SECOND,
+                &[sample(Metric::Cpu, "cpu", second as f64)],
+            );
+        }
+        let now = BASE + 30 * SECOND;
+        assert!(store.lock()[Resolution::Second.index()].needs_compaction(now));
+
+        store.compact(Resolution::Second, now);
+        {
+            let tier = &store.lock()[Resolution::Second.index()];
+            assert_eq!(tier.bytes, 15 * RECORD_LEN, "three quarters of the cap");
+            assert!(!tier.needs_compaction(now));
+        }
+
+        append(&store, BASE + 31 * SECOND, &[]);
+        let series = store
+            .query(Metric::Cpu, 0, u64::MAX, Resolution::Second)
+            .expect("query");
+        let kept = values(&series);
+        assert_eq!(kept.len(), 16);
+        assert_eq!(kept[0], (15, 15.0), "the oldest records go first");
+        assert_eq!(kept[15], (30, 30.0), "appends continue after compaction");
+    }
+}
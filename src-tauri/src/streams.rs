@@ -1,26 +1,53 @@
 //! Lifecycle management for streaming commands.
 //!
-//! Every streaming command registers itself here under a [`StreamName`].
-//! Starting a stream cancels any previous stream with the same name, so a
-//! remount (or React StrictMode double-mount) can never leave two sampling
-//! loops running. The frontend stops a stream explicitly via [`stop_stream`].
+//! Every streaming command registers itself here under a [`StreamName`] and
+//! a [`SubscriberId`], so several windows or widgets can consume the same
+//! stream at once. Starting a stream cancels any previous one with the same
+//! name *and* subscriber, and the frontend stops its own subscription
+//! explicitly via [`stop_stream`].
+//!
+//! The sampling itself happens in [`crate::hub`], which shares one refresh
+//! between every subscriber and stops sampling a subsystem once the last
+//! one leaves; a [`Stream`] is just the control block it checks before
+//! serving a subscriber.
+//!
+//! While a window is minimised or covered its streams back off: `processes`
+//! pauses outright (otherwise Monsoon's own refresh tops its process list)
+//! and everything else drops to [`HIDDEN_SAMPLE_INTERVAL`]. Restoring the
+//! window serves them again on the next tick. Once every window is hidden
+//! the hub's own history sampling backs off to the same interval.
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, Weak,
     },
     time::Duration,
 };
 
-use serde::Deserialize;
-use tauri::State;
+use serde::{Deserialize, Serialize};
+use sysinfo::MINIMUM_CPU_UPDATE_INTERVAL;
+use tauri::{Manager, State, Window, WindowEvent};
+
+use crate::{
+    error::{Error, Result},
+    hub::Hub,
+};
 
-/// How often streaming commands sample the system.
+/// How often streaming commands sample the system unless told otherwise.
 pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+/// Bounds for a caller-supplied interval. Below the floor the refresh work
+/// itself dominates; above the ceiling the UI looks frozen.
+const MIN_SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+const MAX_SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Slowest a stream goes while its window is hidden, unless it already
+/// runs slower than that.
+pub const HIDDEN_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum StreamName {
     Cpu,
@@ -29,29 +56,292 @@ pub enum StreamName {
     Overview,
     Disks,
     Networks,
+    Sensors,
+    Load,
+    Interrupts,
+}
+
+impl StreamName {
+    pub const ALL: [Self; 9] = [
+        Self::Cpu,
+        Self::Memory,
+        Self::Processes,
+        Self::Overview,
+        Self::Disks,
+        Self::Networks,
+        Self::Sensors,
+        Self::Load,
+        Self::Interrupts,
+    ];
+
+    /// Clamps a requested interval to what this stream can meaningfully do.
+    /// Streams reporting CPU usage can't go below sysinfo's minimum delta.
+    fn clamp_interval(self, interval: Duration) -> Duration {
+        let floor = match self {
+            Self::Cpu | Self::Processes | Self::Overview => {
+                MIN_SAMPLE_INTERVAL.max(MINIMUM_CPU_UPDATE_INTERVAL)
+            }
+            Self::Memory
+            | Self::Disks
+            | Self::Networks
+            | Self::Sensors
+            | Self::Load
+            | Self::Interrupts => MIN_SAMPLE_INTERVAL,
+        };
+        interval.clamp(floor, MAX_SAMPLE_INTERVAL)
+    }
+
+    /// Interval to use while the subscriber's window is hidden, or `None`
+    /// to pause the stream until it is shown again.
+    fn hidden_interval(self, interval: Duration) -> Option<Duration> {
+        match self {
+            Self::Processes => None,
+            // Overview also feeds the footer and tray, so it has to keep
+            // going; the rest are cheap enough to just slow down.
+            Self::Cpu
+            | Self::Memory
+            | Self::Overview
+            | Self::Disks
+            | Self::Networks
+            | Self::Sensors
+            | Self::Load
+            | Self::Interrupts => Some(interval.max(HIDDEN_SAMPLE_INTERVAL)),
+        }
+    }
+}
+
+/// One consumer of a stream: the window it lives in plus a token the
+/// frontend generates per subscription.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SubscriberId {
+    window: String,
+    token: String,
+}
+
+impl SubscriberId {
+    pub fn new(window: &Window, token: String) -> Self {
+        Self {
+            window: window.label().to_string(),
+            token,
+        }
+    }
+}
+
+/// Control block shared between the registry and the collector.
+pub struct Stream {
+    name: StreamName,
+    cancelled: AtomicBool,
+    interval_ms: AtomicU64,
+    resync: AtomicBool,
+    hidden: AtomicBool,
+}
+
+impl Stream {
+    fn new(name: StreamName, interval: Duration, hidden: bool) -> Self {
+        Self {
+            name,
+            cancelled: AtomicBool::new(false),
+            interval_ms: AtomicU64::new(duration_ms(interval)),
+            resync: AtomicBool::new(false),
+            hidden: AtomicBool::new(hidden),
+        }
+    }
+
+    pub fn name(&self) -> StreamName {
+        self.name
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Current period between ticks; re-read by the collector every tick so
+    /// [`set_stream_interval`] takes effect without a restart.
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms.load(Ordering::Relaxed))
+    }
+
+    /// Whether the window this stream feeds is minimised or covered.
+    pub fn is_hidden(&self) -> bool {
+        self.hidden.load(Ordering::Relaxed)
+    }
+
+    /// The period to actually sample at, taking a hidden window into
+    /// account; `None` while paused.
+    pub fn effective_interval(&self) -> Option<Duration> {
+        if self.is_hidden() {
+            self.name.hidden_interval(self.interval())
+        } else {
+            Some(self.interval())
+        }
+    }
+
+    /// Whether the frontend asked for a full snapshot since the last call.
+    /// Only meaningful for streams that send deltas.
+    pub fn take_resync(&self) -> bool {
+        self.resync.swap(false, Ordering::Relaxed)
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    fn set_interval(&self, interval: Duration) {
+        self.interval_ms
+            .store(duration_ms(interval), Ordering::Relaxed);
+    }
+}
+
+pub fn duration_ms(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Who reported a window hidden.
+#[derive(Debug, Clone, Copy)]
+enum Hidden {
+    /// The native window: minimised or hidden.
+    Minimized,
+    /// The webview: `document.visibilityState` is `hidden`.
+    Occluded,
 }
 
 #[derive(Default)]
-pub struct StreamRegistry(Mutex<HashMap<StreamName, Arc<AtomicBool>>>);
+struct Inner {
+    /// Weak so a subscription the collector dropped (its webview went
+    /// away) doesn't linger here; dead entries are pruned on `begin`.
+    streams: HashMap<(StreamName, SubscriberId), Weak<Stream>>,
+    /// Last interval chosen per stream, so a restart (e.g. navigating back
+    /// to a page) keeps it.
+    intervals: HashMap<StreamName, Duration>,
+    /// Every window we've heard from and that hasn't been destroyed.
+    windows: HashSet<String>,
+    /// Window labels as reported hidden by the native window and by the
+    /// webview; a window counts as hidden if either says so.
+    minimized: HashSet<String>,
+    occluded: HashSet<String>,
+}
+
+impl Inner {
+    fn is_hidden(&self, window: &str) -> bool {
+        self.minimized.contains(window) || self.occluded.contains(window)
+    }
+
+    fn all_hidden(&self) -> bool {
+        !self.windows.is_empty() && self.windows.iter().all(|window| self.is_hidden(window))
+    }
+}
+
+#[derive(Default)]
+pub struct StreamRegistry(Mutex<Inner>);
 
 impl StreamRegistry {
-    /// Cancels any live stream with the same name and returns a fresh
-    /// cancellation flag for the new one.
-    pub fn begin(&self, name: StreamName) -> Arc<AtomicBool> {
-        let flag = Arc::new(AtomicBool::new(false));
-        if let Some(previous) = self.lock().insert(name, flag.clone()) {
-            previous.store(true, Ordering::Relaxed);
+    /// Cancels any live stream with the same name and subscriber and
+    /// returns the control block for the new one. `interval_ms` overrides
+    /// the remembered interval for this stream.
+    pub fn begin(
+        &self,
+        name: StreamName,
+        subscriber: SubscriberId,
+        interval_ms: Option<u64>,
+    ) -> Arc<Stream> {
+        let mut inner = self.lock();
+        inner.streams.retain(|_, stream| stream.strong_count() > 0);
+        if let Some(ms) = interval_ms {
+            inner
+                .intervals
+                .insert(name, name.clamp_interval(Duration::from_millis(ms)));
+        }
+        let interval = inner
+            .intervals
+            .get(&name)
+            .copied()
+            .unwrap_or(SAMPLE_INTERVAL);
+
+        inner.windows.insert(subscriber.window.clone());
+        let hidden = inner.is_hidden(&subscriber.window);
+        let stream = Arc::new(Stream::new(name, interval, hidden));
+        let previous = inner
+            .streams
+            .insert((name, subscriber), Arc::downgrade(&stream));
+        if let Some(previous) = previous.and_then(|weak| weak.upgrade()) {
+            previous.cancel();
+        }
+        stream
+    }
+
+    pub fn stop(&self, name: StreamName, subscriber: SubscriberId) {
+        let removed = self.lock().streams.remove(&(name, subscriber));
+        if let Some(stream) = removed.and_then(|weak| weak.upgrade()) {
+            stream.cancel();
         }
-        flag
     }
 
-    pub fn stop(&self, name: StreamName) {
-        if let Some(flag) = self.lock().get(&name) {
-            flag.store(true, Ordering::Relaxed);
+    /// Returns `false` if no such stream is running.
+    pub fn resync(&self, name: StreamName, subscriber: SubscriberId) -> bool {
+        let stream = self.lock().streams.get(&(name, subscriber)).cloned();
+        match stream.and_then(|weak| weak.upgrade()) {
+            Some(stream) => {
+                stream.resync.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
         }
     }
 
-    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<StreamName, Arc<AtomicBool>>> {
+    /// Changes the interval of every subscription to a stream and of
+    /// future ones, returning the interval actually applied after clamping.
+    pub fn set_interval(&self, name: StreamName, interval: Duration) -> Duration {
+        let interval = name.clamp_interval(interval);
+        let mut inner = self.lock();
+        inner.intervals.insert(name, interval);
+        inner
+            .streams
+            .iter()
+            .filter(|((stream_name, _), _)| *stream_name == name)
+            .filter_map(|(_, weak)| weak.upgrade())
+            .for_each(|stream| stream.set_interval(interval));
+        interval
+    }
+
+    /// Records one source's view of `window` and updates its streams.
+    /// Returns whether every window is now hidden, or `None` if the
+    /// window's overall visibility didn't change.
+    fn set_hidden(&self, window: &str, source: Hidden, hidden: bool) -> Option<bool> {
+        let mut inner = self.lock();
+        inner.windows.insert(window.to_string());
+        let was_hidden = inner.is_hidden(window);
+        let set = match source {
+            Hidden::Minimized => &mut inner.minimized,
+            Hidden::Occluded => &mut inner.occluded,
+        };
+        if hidden {
+            set.insert(window.to_string());
+        } else {
+            set.remove(window);
+        }
+        let is_hidden = inner.is_hidden(window);
+        if is_hidden == was_hidden {
+            return None;
+        }
+        inner
+            .streams
+            .iter()
+            .filter(|((_, subscriber), _)| subscriber.window == window)
+            .filter_map(|(_, weak)| weak.upgrade())
+            .for_each(|stream| stream.hidden.store(is_hidden, Ordering::Relaxed));
+        Some(inner.all_hidden())
+    }
+
+    /// Returns whether every remaining window is hidden.
+    fn forget_window(&self, window: &str) -> bool {
+        let mut inner = self.lock();
+        inner.windows.remove(window);
+        inner.minimized.remove(window);
+        inner.occluded.remove(window);
+        inner.all_hidden()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
         // A poisoned lock only means another sampler panicked; the map of
         // flags is still valid, so keep going instead of propagating.
         self.0
@@ -60,7 +350,77 @@ impl StreamRegistry {
     }
 }
 
+/// Hooked into the builder's `on_window_event`. There is no native event
+/// for minimising, so every resize and focus change re-checks the state.
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    let registry = window.state::<StreamRegistry>();
+    match event {
+        WindowEvent::Resized(_) | WindowEvent::Focused(_) => {
+            let hidden =
+                window.is_minimized().unwrap_or(false) || !window.is_visible().unwrap_or(true);
+            if let Some(all_hidden) = registry.set_hidden(window.label(), Hidden::Minimized, hidden)
+            {
+                window.state::<Hub>().set_background(all_hidden);
+            }
+        }
+        WindowEvent::Destroyed => {
+            let all_hidden = registry.forget_window(window.label());
+            window.state::<Hub>().set_background(all_hidden);
+        }
+        _ => {}
+    }
+}
+
+#[tauri::command]
+pub fn stop_stream(
+    registry: State<'_, StreamRegistry>,
+    window: Window,
+    stream: StreamName,
+    token: String,
+) {
+    registry.stop(stream, SubscriberId::new(&window, token));
+}
+
+/// Asks a delta-encoded stream to send a full snapshot on its next tick,
+/// e.g. after the frontend spotted a gap in sequence numbers.
+#[tauri::command]
+pub fn resync_stream(
+    registry: State<'_, StreamRegistry>,
+    window: Window,
+    stream: StreamName,
+    token: String,
+) -> Result<()> {
+    if registry.resync(stream, SubscriberId::new(&window, token)) {
+        Ok(())
+    } else {
+        Err(Error::StreamCancelled { stream })
+    }
+}
+
+/// The webview's `visibilitychange`, which also catches a window that is
+/// fully covered by others but not minimised.
+#[tauri::command]
+pub fn set_window_occluded(
+    registry: State<'_, StreamRegistry>,
+    hub: State<'_, Hub>,
+    window: Window,
+    occluded: bool,
+) {
+    if let Some(all_hidden) = registry.set_hidden(window.label(), Hidden::Occluded, occluded) {
+        hub.set_background(all_hidden);
+    }
+}
+
+/// Returns the applied interval in milliseconds, which may differ from the
+/// requested one if it was out of bounds.
 #[tauri::command]
-pub fn stop_stream(registry: State<'_, StreamRegistry>, stream: StreamName) {
-    registry.stop(stream);
+pub fn set_stream_interval(
+    registry: State<'_, StreamRegistry>,
+    hub: State<'_, Hub>,
+    stream: StreamName,
+    ms: u64,
+) -> u64 {
+    let applied = registry.set_interval(stream, Duration::from_millis(ms));
+    hub.reschedule();
+    duration_ms(applied)
 }
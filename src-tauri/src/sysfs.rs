@@ -0,0 +1,97 @@
+//! Small helpers for reading sysfs attribute files.
+//!
+//! Everything that reads sysfs takes its root as a parameter (normally
+//! [`ROOT`]) so it can be pointed at a fixture directory in tests.
+
+use std::{fs, path::Path};
+
+#[cfg(test)]
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Where sysfs is mounted.
+pub const ROOT: &str = "/sys";
+
+/// An attribute's contents without the trailing newline, or `None` if it
+/// doesn't exist or can't be read (many need root, or vanish with hotplug).
+pub fn read_string(path: &Path) -> Option<String> {
+    let contents = fs::read_to_string(path).ok()?;
+    Some(contents.trim_end().to_string())
+}
+
+/// An attribute holding a single integer.
+pub fn read_i64(path: &Path) -> Option<i64> {
+    read_string(path)?.parse().ok()
+}
+
+/// Parses a kernel CPU list such as `0-3,8,10-11`.
+pub fn parse_cpu_list(list: &str) -> Vec<u32> {
+    list.trim()
+        .split(',')
+        .filter(|range| !range.is_empty())
+        .filter_map(|range| {
+            let (start, end) = range.split_once('-').unwrap_or((range, range));
+            Some(start.parse::<u32>().ok()?..=end.parse().ok()?)
+        })
+        .flatten()
+        .collect()
+}
+
+/// A throwaway sysfs (or procfs) tree under the temp dir for tests. It is
+/// removed on drop, so a failing assert doesn't leave it behind.
+#[cfg(test)]
+pub struct Fixture(PathBuf);
+
+#[cfg(test)]
+impl Fixture {
+    /// `name` only makes a leftover tree easier to trace back to its test.
+    pub fn new(name: &str) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let root = std::env::temp_dir().join(format!(
+            "monsoon-{name}-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&root).expect("create fixture");
+        Self(root)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.0
+    }
+
+    /// Writes `contents` and the trailing newline kernel files end with to
+    /// `path`, relative to the root, creating its parent directories.
+    pub fn write(&self, path: impl AsRef<Path>, contents: &str) {
+        let path = self.0.join(path);
+        fs::create_dir_all(path.parent().expect("fixture file has a parent"))
+            .expect("create fixture");
+        fs::write(path, format!("{contents}\n")).expect("write fixture");
+    }
+
+    /// Creates an empty directory at `path`, relative to the root.
+    pub fn create_dir(&self, path: impl AsRef<Path>) {
+        fs::create_dir_all(self.0.join(path)).expect("create fixture");
+    }
+}
+
+#[cfg(test)]
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        // Best effort; a tree left in the temp dir is harmless.
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cpu_lists() {
+        assert_eq!(parse_cpu_list("0-3,8,10-11\n"), [0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_cpu_list(""), Vec::<u32>::new());
+    }
+}
@@ -1,6 +1,10 @@
+use std::path::Path;
+
 use serde::Serialize;
 use sysinfo::System;
 
+use crate::environment::{self, Environment};
+
 #[derive(Debug, Serialize)]
 pub struct SystemInfo {
     os_name: String,
@@ -11,6 +15,8 @@ pub struct SystemInfo {
     distribution_id: String,
     cpu_arch: String,
     uptime: u64,
+    /// VM and container context, for reading the other numbers.
+    environment: Environment,
 }
 
 #[tauri::command]
@@ -24,5 +30,6 @@ pub fn get_system_info() -> SystemInfo {
         distribution_id: System::distribution_id(),
         cpu_arch: System::cpu_arch(),
         uptime: System::uptime(),
+        environment: environment::environment(Path::new("/")),
     }
 }
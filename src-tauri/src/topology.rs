@@ -0,0 +1,329 @@
+//! How logical CPUs map onto packages, dies, cores, NUMA nodes and caches.
+//!
+//! Read from `devices/system/cpu/cpuN/{topology,cache}` and
+//! `devices/system/node` in sysfs, so Linux only; other platforms get an
+//! empty topology and the frontend falls back to a flat core list. The same
+//! cache directories describe cache geometry where `cpuid` can't.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::{
+    cpufreq,
+    cpuid::{self, CacheInfo, CoreType},
+    sysfs,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogicalCpu {
+    /// As in `CoreSnapshot::name` (`cpu3`).
+    pub name: String,
+    pub id: u32,
+    /// Socket.
+    pub package: Option<u32>,
+    pub die: Option<u32>,
+    /// Physical core id, unique within its package (not across packages).
+    pub core: Option<u32>,
+    /// Hardware threads on the same physical core, including this one.
+    pub siblings: Vec<u32>,
+    pub numa_node: Option<u32>,
+    pub caches: Vec<CacheInstance>,
+    /// Only set on hybrid parts.
+    pub core_type: Option<CoreType>,
+    /// See [`CoreClasses::frequency_class`].
+    pub frequency_class: Option<u32>,
+}
+
+/// The logical CPU number behind a sysinfo CPU name (`cpu3`).
+pub fn cpu_id(name: &str) -> Option<u32> {
+    name.strip_prefix("cpu")?.parse().ok()
+}
+
+/// What kind of core each logical CPU is. This can't change while we run,
+/// so it's read once rather than on every refresh.
+#[derive(Debug, Default)]
+pub struct CoreClasses {
+    types: HashMap<u32, CoreType>,
+    frequency: HashMap<u32, u32>,
+}
+
+impl CoreClasses {
+    pub fn load(sysfs_root: &Path) -> Self {
+        Self {
+            types: cpuid::core_types(sysfs_root),
+            frequency: cpufreq::frequency_classes(&cpufreq::policies(sysfs_root)),
+        }
+    }
+
+    pub fn core_type(&self, cpu: u32) -> Option<CoreType> {
+        self.types.get(&cpu).copied()
+    }
+
+    /// 0 for the CPUs with the highest maximum frequency, 1 for the next
+    /// highest, and so on; everything is 0 on a homogeneous part.
+    pub fn frequency_class(&self, cpu: u32) -> Option<u32> {
+        self.frequency.get(&cpu).copied()
+    }
+}
+
+/// One cache this CPU uses, and who else uses the same one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CacheInstance {
+    /// Matches `CacheInfo::label` from [`crate::cpuid::cache_sizes`],
+    /// e.g. "L2 Unified Cache".
+    pub label: String,
+    /// Identifies the instance among caches with the same label.
+    pub instance: u32,
+    pub shared_with: Vec<u32>,
+}
+
+fn read_u32(path: &Path) -> Option<u32> {
+    sysfs::read_i64(path).and_then(|value| u32::try_from(value).ok())
+}
+
+/// `(id, directory)` of every `prefix<N>` entry in `dir`, by id.
+pub fn numbered_dirs(dir: &Path, prefix: &str) -> Vec<(u32, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<(u32, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            Some((name.strip_prefix(prefix)?.parse().ok()?, entry.path()))
+        })
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn cache_label(level: &str, kind: &str) -> String {
+    format!("L{level} {kind} Cache")
+}
+
+/// Parses a cache size such as "48K" or "36M".
+fn parse_size(size: &str) -> Option<usize> {
+    let split = size
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(size.len());
+    let (digits, unit) = size.split_at(split);
+    let multiplier = match unit {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        _ => return None,
+    };
+    Some(digits.parse::<usize>().ok()? * multiplier)
+}
+
+fn read_cache_info(dir: &Path) -> Option<CacheInfo> {
+    let level = sysfs::read_string(&dir.join("level"))?;
+    let kind = sysfs::read_string(&dir.join("type"))?;
+    // Unknown geometry reads as 0 on some platforms.
+    let read_usize = |file: &str| {
+        sysfs::read_i64(&dir.join(file))
+            .and_then(|value| usize::try_from(value).ok())
+            .filter(|&value| value > 0)
+    };
+    let associativity = read_usize("ways_of_associativity");
+    let sets = read_usize("number_of_sets");
+    Some(CacheInfo {
+        label: cache_label(&level, &kind),
+        bytes: parse_size(&sysfs::read_string(&dir.join("size"))?)?,
+        level: level.parse().ok()?,
+        associativity,
+        line_size: read_usize("coherency_line_size"),
+        sets,
+        partitions: read_usize("physical_line_partition"),
+        shared_by: sysfs::read_string(&dir.join("shared_cpu_list"))
+            .map(|list| sysfs::parse_cpu_list(&list).len())
+            .unwrap_or(1),
+        inclusive: None,
+        fully_associative: sets == Some(1) && associativity.is_some_and(|ways| ways > 1),
+    })
+}
+
+/// Each distinct cache (by label) of the online CPUs, in the shape
+/// [`crate::cpuid::cache_sizes`] returns, for CPUs it can't describe. The
+/// first CPU with a given cache wins, so differing clusters on big.LITTLE
+/// parts show up as one entry, as they would from `cpuid`.
+pub fn caches(sysfs_root: &Path) -> Vec<CacheInfo> {
+    let mut caches: Vec<CacheInfo> = Vec::new();
+    for (_, cpu_dir) in numbered_dirs(&sysfs_root.join("devices/system/cpu"), "cpu") {
+        for (_, dir) in numbered_dirs(&cpu_dir.join("cache"), "index") {
+            if let Some(cache) = read_cache_info(&dir) {
+                if !caches.iter().any(|known| known.label == cache.label) {
+                    caches.push(cache);
+                }
+            }
+        }
+    }
+    caches
+}
+
+fn read_caches(cpu_dir: &Path) -> Vec<CacheInstance> {
+    numbered_dirs(&cpu_dir.join("cache"), "index")
+        .into_iter()
+        .filter_map(|(_, dir)| {
+            let level = sysfs::read_string(&dir.join("level"))?;
+            let kind = sysfs::read_string(&dir.join("type"))?;
+            let shared_with =
+                sysfs::parse_cpu_list(&sysfs::read_string(&dir.join("shared_cpu_list"))?);
+            // `id` is missing on older kernels; the first CPU sharing the
+            // cache identifies it just as well.
+            let instance = read_u32(&dir.join("id")).or(shared_with.first().copied())?;
+            Some(CacheInstance {
+                label: cache_label(&level, &kind),
+                instance,
+                shared_with,
+            })
+        })
+        .collect()
+}
+
+/// Every online logical CPU, by id.
+pub fn topology(sysfs_root: &Path) -> Vec<LogicalCpu> {
+    let classes = CoreClasses::load(sysfs_root);
+    let nodes: Vec<(u32, Vec<u32>)> =
+        numbered_dirs(&sysfs_root.join("devices/system/node"), "node")
+            .into_iter()
+            .map(|(id, dir)| {
+                let cpus = sysfs::read_string(&dir.join("cpulist")).unwrap_or_default();
+                (id, sysfs::parse_cpu_list(&cpus))
+            })
+            .collect();
+
+    numbered_dirs(&sysfs_root.join("devices/system/cpu"), "cpu")
+        .into_iter()
+        // Offline CPUs keep their directory but lose `topology`.
+        .filter(|(_, dir)| dir.join("topology").is_dir())
+        .map(|(id, dir)| {
+            let topology = dir.join("topology");
+            LogicalCpu {
+                name: format!("cpu{id}"),
+                id,
+                package: read_u32(&topology.join("physical_package_id")),
+                // None where the platform has no notion of dies (-1).
+                die: read_u32(&topology.join("die_id")),
+                core: read_u32(&topology.join("core_id")),
+                siblings: sysfs::read_string(&topology.join("thread_siblings_list"))
+                    .map(|list| sysfs::parse_cpu_list(&list))
+                    .unwrap_or_else(|| vec![id]),
+                numa_node: nodes
+                    .iter()
+                    .find(|(_, cpus)| cpus.contains(&id))
+                    .map(|(node, _)| *node),
+                caches: read_caches(&dir),
+                core_type: classes.core_type(id),
+                frequency_class: classes.frequency_class(id),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::Fixture;
+
+    #[test]
+    fn maps_smt_siblings_nodes_caches_and_core_types() {
+        let fixture = Fixture::new("topology");
+        let root = fixture.root();
+        let cpus = Path::new("devices/system/cpu");
+        // Two packages of one core with two threads each; cpu0/cpu2 and
+        // cpu1/cpu3 are siblings, as Linux enumerates them on x86.
+        for id in 0..4u32 {
+            let cpu = cpus.join(format!("cpu{id}"));
+            let package = id % 2;
+            fixture.write(
+                cpu.join("topology/physical_package_id"),
+                &package.to_string(),
+            );
+            fixture.write(cpu.join("topology/die_id"), "0");
+            fixture.write(cpu.join("topology/core_id"), "0");
+            let siblings = if package == 0 { "0,2" } else { "1,3" };
+            fixture.write(cpu.join("topology/thread_siblings_list"), siblings);
+            fixture.write(cpu.join("cache/index0/level"), "1");
+            fixture.write(cpu.join("cache/index0/type"), "Data");
+            fixture.write(cpu.join("cache/index0/shared_cpu_list"), siblings);
+            fixture.write(cpu.join("cache/index3/level"), "3");
+            fixture.write(cpu.join("cache/index3/type"), "Unified");
+            fixture.write(cpu.join("cache/index3/shared_cpu_list"), siblings);
+            fixture.write(cpu.join("cache/index3/id"), &package.to_string());
+            fixture.write(cpu.join("cache/index0/size"), "48K");
+            fixture.write(cpu.join("cache/index0/ways_of_associativity"), "12");
+            fixture.write(cpu.join("cache/index0/coherency_line_size"), "64");
+            fixture.write(cpu.join("cache/index0/number_of_sets"), "64");
+            fixture.write(cpu.join("cache/index0/physical_line_partition"), "1");
+            // Geometry the platform doesn't know.
+            fixture.write(cpu.join("cache/index3/size"), "8M");
+            fixture.write(cpu.join("cache/index3/ways_of_associativity"), "0");
+        }
+        fixture.create_dir(cpus.join("cpu4"));
+        fixture.write("devices/system/node/node0/cpulist", "0,2");
+        fixture.write("devices/system/node/node1/cpulist", "1,3");
+        // Make package 1 the efficiency cluster.
+        fixture.write("devices/cpu_core/cpus", "0,2");
+        fixture.write("devices/cpu_atom/cpus", "1,3");
+        for (id, affected, max) in [(0, "0 2", "5000000"), (1, "1 3", "3800000")] {
+            let policy = cpus.join(format!("cpufreq/policy{id}"));
+            fixture.write(policy.join("affected_cpus"), affected);
+            fixture.write(policy.join("cpuinfo_max_freq"), max);
+        }
+
+        let topology = topology(root);
+        let caches = caches(root);
+
+        assert_eq!(caches.len(), 2, "one entry per distinct cache");
+        assert_eq!(
+            caches[0],
+            CacheInfo {
+                label: "L1 Data Cache".to_string(),
+                bytes: 48 << 10,
+                level: 1,
+                associativity: Some(12),
+                line_size: Some(64),
+                sets: Some(64),
+                partitions: Some(1),
+                shared_by: 2,
+                inclusive: None,
+                fully_associative: false,
+            }
+        );
+        assert_eq!(caches[1].bytes, 8 << 20);
+        assert_eq!(caches[1].associativity, None);
+
+        assert_eq!(topology.len(), 4, "offline cpu4 is skipped");
+        let cpu3 = &topology[3];
+        assert_eq!(cpu3.name, "cpu3");
+        assert_eq!(cpu3.package, Some(1));
+        assert_eq!(cpu3.siblings, [1, 3]);
+        assert_eq!(cpu3.numa_node, Some(1));
+        assert_eq!(cpu3.core_type, Some(CoreType::Efficiency));
+        assert_eq!(cpu3.frequency_class, Some(1));
+        assert_eq!(topology[2].core_type, Some(CoreType::Performance));
+        assert_eq!(topology[2].frequency_class, Some(0));
+        assert_eq!(
+            cpu3.caches,
+            [
+                CacheInstance {
+                    label: "L1 Data Cache".to_string(),
+                    instance: 1,
+                    shared_with: vec![1, 3],
+                },
+                CacheInstance {
+                    label: "L3 Unified Cache".to_string(),
+                    instance: 1,
+                    shared_with: vec![1, 3],
+                },
+            ]
+        );
+    }
+}
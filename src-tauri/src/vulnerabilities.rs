@@ -0,0 +1,114 @@
+//! The kernel's view of CPU side-channel vulnerabilities and the microcode
+//! it is running with, from `devices/system/cpu` in sysfs. Linux only.
+
+use std::{fs, path::Path};
+
+use serde::Serialize;
+
+use crate::sysfs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VulnerabilityStatus {
+    NotAffected,
+    Mitigated,
+    Vulnerable,
+    /// The kernel couldn't tell, or reported something we don't recognise.
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Vulnerability {
+    /// File name, e.g. `spectre_v2`.
+    pub name: String,
+    pub status: VulnerabilityStatus,
+    /// The kernel's full report, e.g. "Mitigation: Enhanced / Automatic IBRS".
+    pub detail: String,
+}
+
+fn classify(report: &str) -> VulnerabilityStatus {
+    // Some are prefixed with the subsystem that mitigates them, as in
+    // "KVM: Mitigation: VMX disabled".
+    let report = report.strip_prefix("KVM: ").unwrap_or(report);
+    if report.starts_with("Not affected") {
+        VulnerabilityStatus::NotAffected
+    } else if report.starts_with("Vulnerable") {
+        VulnerabilityStatus::Vulnerable
+    } else if report.starts_with("Mitigation") {
+        VulnerabilityStatus::Mitigated
+    } else {
+        VulnerabilityStatus::Unknown
+    }
+}
+
+/// Every vulnerability the running kernel knows about, by name.
+pub fn vulnerabilities(sysfs_root: &Path) -> Vec<Vulnerability> {
+    let dir = sysfs_root.join("devices/system/cpu/vulnerabilities");
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut vulnerabilities: Vec<Vulnerability> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let detail = sysfs::read_string(&entry.path())?;
+            Some(Vulnerability {
+                name: entry.file_name().into_string().ok()?,
+                status: classify(&detail),
+                detail,
+            })
+        })
+        .collect();
+    vulnerabilities.sort_by(|a, b| a.name.cmp(&b.name));
+    vulnerabilities
+}
+
+/// The loaded microcode revision as the kernel prints it (`0x2b000603`).
+/// CPUs can differ after a partial late load; cpu0 is representative.
+pub fn microcode_revision(sysfs_root: &Path) -> Option<String> {
+    sysfs::read_string(&sysfs_root.join("devices/system/cpu/cpu0/microcode/version"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::Fixture;
+
+    #[test]
+    fn classifies_kernel_reports() {
+        let fixture = Fixture::new("vulnerabilities");
+        let dir = Path::new("devices/system/cpu/vulnerabilities");
+        for (name, report) in [
+            (
+                "spectre_v2",
+                "Mitigation: Enhanced / Automatic IBRS; IBPB: conditional",
+            ),
+            ("meltdown", "Not affected"),
+            ("itlb_multihit", "KVM: Mitigation: VMX disabled"),
+            (
+                "mds",
+                "Vulnerable: Clear CPU buffers attempted, no microcode; SMT vulnerable",
+            ),
+            ("gds", "Unknown: Dependent on hypervisor status"),
+        ] {
+            fixture.write(dir.join(name), report);
+        }
+
+        let vulnerabilities = vulnerabilities(fixture.root());
+
+        let statuses: Vec<(&str, VulnerabilityStatus)> = vulnerabilities
+            .iter()
+            .map(|vulnerability| (vulnerability.name.as_str(), vulnerability.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("gds", VulnerabilityStatus::Unknown),
+                ("itlb_multihit", VulnerabilityStatus::Mitigated),
+                ("mds", VulnerabilityStatus::Vulnerable),
+                ("meltdown", VulnerabilityStatus::NotAffected),
+                ("spectre_v2", VulnerabilityStatus::Mitigated),
+            ]
+        );
+        assert_eq!(vulnerabilities[1].detail, "KVM: Mitigation: VMX disabled");
+    }
+}
@@ -12,6 +12,7 @@ import {
   ResizablePanel,
   ResizablePanelGroup,
 } from "@/components/ui/resizable";
+import { useWindowVisibility } from "@/hooks/use-window-visibility";
 
 // Route-level code splitting keeps recharts out of the initial chunk.
 const Dashboard = lazy(() =>
@@ -30,8 +31,16 @@ const Disks = lazy(() =>
 const Network = lazy(() =>
   import("@/pages/network").then((m) => ({ default: m.Network })),
 );
+const Sensors = lazy(() =>
+  import("@/pages/sensors").then((m) => ({ default: m.Sensors })),
+);
+const Interrupts = lazy(() =>
+  import("@/pages/interrupts").then((m) => ({ default: m.Interrupts })),
+);
 
 function App() {
+  useWindowVisibility();
+
   return (
     <ThemeProvider>
       <MetricsProvider>
@@ -55,6 +64,8 @@ function App() {
                         <Route path="/processes" element={<Processes />} />
                         <Route path="/disks" element={<Disks />} />
                         <Route path="/network" element={<Network />} />
+                        <Route path="/sensors" element={<Sensors />} />
+                        <Route path="/interrupts" element={<Interrupts />} />
                         <Route path="*" element={<Navigate to="/" replace />} />
                       </Routes>
                     </Suspense>
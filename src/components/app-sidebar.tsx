@@ -1,10 +1,12 @@
 import {
+  IconBolt,
   IconCpu,
   IconDatabase,
   IconDeviceSdCard,
   IconLayoutDashboard,
   IconListDetails,
   IconNetwork,
+  IconTemperature,
 } from "@tabler/icons-react";
 import { NavLink } from "react-router-dom";
 
@@ -18,6 +20,8 @@ const NAV_ITEMS = [
   { to: "/processes", label: "Processes", icon: IconListDetails },
   { to: "/disks", label: "Disks", icon: IconDatabase },
   { to: "/network", label: "Network", icon: IconNetwork },
+  { to: "/sensors", label: "Sensors", icon: IconTemperature },
+  { to: "/interrupts", label: "Interrupts", icon: IconBolt },
 ];
 
 export function AppSidebar() {
@@ -1,4 +1,10 @@
 import { formatPercent } from "@/lib/format";
+import type { CoreType, LogicalCpu } from "@/types/system";
+
+const CORE_TYPE_PREFIX: Record<CoreType, string> = {
+  performance: "P",
+  efficiency: "E",
+};
 
 /**
  * Load → color: cyan (idle) → amber (busy) → red (saturated).
@@ -19,9 +25,75 @@ type CoreHeatmapProps = {
   usages: number[];
   /** Compact strip (dashboard) vs labeled cells (CPU page). */
   variant?: "strip" | "cells";
+  /**
+   * Groups cells by socket and physical core. Usages are looked up by CPU
+   * id, which is also their index on Linux, the only place it's reported.
+   */
+  topology?: LogicalCpu[];
 };
 
-export function CoreHeatmap({ usages, variant = "strip" }: CoreHeatmapProps) {
+function CoreCell({
+  index,
+  usage,
+  coreType,
+}: {
+  index: number;
+  usage: number;
+  coreType?: CoreType | null;
+}) {
+  return (
+    <div className="flex min-w-0 flex-1 flex-col items-center gap-0.5 rounded-md border border-border/60 px-1 py-1.5">
+      <span
+        className="text-[10px] text-muted-foreground"
+        title={coreType ? `${coreType} core` : undefined}
+      >
+        {coreType ? CORE_TYPE_PREFIX[coreType] : "C"}
+        {index}
+      </span>
+      <span
+        className="stat-figure text-xs font-semibold"
+        style={{ color: heatColor(usage) }}
+      >
+        {formatPercent(usage, 0)}
+      </span>
+      <div className="h-1 w-full overflow-hidden rounded-full bg-muted">
+        <div
+          className="h-full rounded-full transition-[width] duration-500"
+          style={{
+            width: `${Math.min(usage, 100)}%`,
+            backgroundColor: heatColor(usage),
+          }}
+        />
+      </div>
+    </div>
+  );
+}
+
+/** Sockets, each with its physical cores as lists of SMT sibling ids. */
+function groupBySocket(topology: LogicalCpu[]) {
+  const sockets = new Map<number, Map<number, number[]>>();
+  for (const cpu of topology) {
+    const socket = cpu.package ?? 0;
+    const cores = sockets.get(socket) ?? new Map<number, number[]>();
+    const threads = cpu.siblings.length > 0 ? cpu.siblings : [cpu.id];
+    cores.set(Math.min(...threads), threads);
+    sockets.set(socket, cores);
+  }
+  return [...sockets.entries()]
+    .sort(([a], [b]) => a - b)
+    .map(([socket, cores]) => ({
+      socket,
+      cores: [...cores.entries()]
+        .sort(([a], [b]) => a - b)
+        .map(([, threads]) => threads),
+    }));
+}
+
+export function CoreHeatmap({
+  usages,
+  variant = "strip",
+  topology,
+}: CoreHeatmapProps) {
   if (variant === "strip") {
     return (
       <div className="flex flex-wrap gap-1" aria-label="Per-core load">
@@ -40,33 +112,49 @@ export function CoreHeatmap({ usages, variant = "strip" }: CoreHeatmapProps) {
     );
   }
 
+  if (topology && topology.length > 0) {
+    const sockets = groupBySocket(topology);
+    const coreTypes = new Map(topology.map((cpu) => [cpu.id, cpu.core_type]));
+    return (
+      <div className="flex flex-col gap-3" aria-label="Per-core load">
+        {sockets.map(({ socket, cores }) => (
+          <div key={socket} className="flex flex-col gap-1.5">
+            {sockets.length > 1 && (
+              <span className="text-[11px] font-medium text-muted-foreground">
+                Socket {socket}
+              </span>
+            )}
+            <div className="grid grid-cols-[repeat(auto-fill,minmax(152px,1fr))] gap-1.5">
+              {cores.map((threads) => (
+                <div
+                  key={threads[0]}
+                  className="flex gap-1 rounded-lg border border-dashed border-border p-1"
+                  title={`Threads ${threads.join(", ")} share a physical core`}
+                >
+                  {threads.map((id) => (
+                    <CoreCell
+                      key={id}
+                      index={id}
+                      usage={usages[id] ?? 0}
+                      coreType={coreTypes.get(id)}
+                    />
+                  ))}
+                </div>
+              ))}
+            </div>
+          </div>
+        ))}
+      </div>
+    );
+  }
+
   return (
     <div
       className="grid grid-cols-[repeat(auto-fill,minmax(72px,1fr))] gap-1.5"
       aria-label="Per-core load"
     >
       {usages.map((usage, index) => (
-        <div
-          key={index}
-          className="flex flex-col items-center gap-0.5 rounded-md border border-border/60 px-1 py-1.5"
-        >
-          <span className="text-[10px] text-muted-foreground">C{index}</span>
-          <span
-            className="stat-figure text-xs font-semibold"
-            style={{ color: heatColor(usage) }}
-          >
-            {formatPercent(usage, 0)}
-          </span>
-          <div className="h-1 w-full overflow-hidden rounded-full bg-muted">
-            <div
-              className="h-full rounded-full transition-[width] duration-500"
-              style={{
-                width: `${Math.min(usage, 100)}%`,
-                backgroundColor: heatColor(usage),
-              }}
-            />
-          </div>
-        </div>
+        <CoreCell key={index} index={index} usage={usage} />
       ))}
     </div>
   );
@@ -0,0 +1,55 @@
+import { formatPercent } from "@/lib/format";
+import type { CpuTimes } from "@/types/system";
+
+/** Busy states in stacking order; idle is the empty remainder of the bar. */
+const STATES: { key: Exclude<keyof CpuTimes, "idle">; color: string }[] = [
+  { key: "user", color: "var(--chart-1)" },
+  { key: "nice", color: "var(--chart-3)" },
+  { key: "system", color: "var(--chart-2)" },
+  { key: "iowait", color: "var(--chart-4)" },
+  { key: "irq", color: "var(--chart-5)" },
+  { key: "softirq", color: "var(--chart-5)" },
+  { key: "steal", color: "var(--destructive)" },
+  { key: "guest", color: "var(--muted-foreground)" },
+];
+
+type CpuTimesBarProps = {
+  times: CpuTimes;
+  /** Show a legend with each state's share under the bar. */
+  legend?: boolean;
+};
+
+export function CpuTimesBar({ times, legend = false }: CpuTimesBarProps) {
+  return (
+    <div className="flex flex-col gap-1.5">
+      <div className="flex h-2 overflow-hidden rounded-full bg-muted">
+        {STATES.map(({ key, color }) => (
+          <div
+            key={key}
+            title={`${key} ${formatPercent(times[key])}`}
+            className="h-full transition-[width] duration-500"
+            style={{ width: `${times[key]}%`, backgroundColor: color }}
+          />
+        ))}
+      </div>
+      {legend && (
+        <ul className="flex flex-wrap gap-x-3 gap-y-1 text-xs text-muted-foreground">
+          {[...STATES, { key: "idle" as const, color: "var(--muted)" }].map(
+            ({ key, color }) => (
+              <li key={key} className="flex items-center gap-1">
+                <span
+                  className="size-2 rounded-full"
+                  style={{ backgroundColor: color }}
+                />
+                {key}
+                <span className="stat-figure text-foreground">
+                  {formatPercent(times[key])}
+                </span>
+              </li>
+            ),
+          )}
+        </ul>
+      )}
+    </div>
+  );
+}
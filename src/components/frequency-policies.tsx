@@ -0,0 +1,118 @@
+import { IconChevronDown } from "@tabler/icons-react";
+import { invoke } from "@tauri-apps/api/core";
+import { useState } from "react";
+
+import { Button } from "@/components/ui/button";
+import {
+  DropdownMenu,
+  DropdownMenuContent,
+  DropdownMenuLabel,
+  DropdownMenuRadioGroup,
+  DropdownMenuRadioItem,
+  DropdownMenuTrigger,
+} from "@/components/ui/dropdown-menu";
+import { toCommandError } from "@/lib/errors";
+import { formatFrequency } from "@/lib/format";
+import type {
+  CommandError,
+  CoreSnapshot,
+  FrequencyPolicy,
+} from "@/types/system";
+
+type FrequencyPoliciesProps = {
+  policies: FrequencyPolicy[];
+  boost: boolean | null;
+  /** Latest live cores, to show each policy's current governor. */
+  cores: CoreSnapshot[];
+};
+
+export function FrequencyPolicies({
+  policies,
+  boost,
+  cores,
+}: FrequencyPoliciesProps) {
+  const [error, setError] = useState<CommandError | string | null>(null);
+
+  const scalingOf = (policy: FrequencyPolicy) =>
+    cores.find((core) => core.name === `cpu${policy.cpus[0]}`)?.scaling;
+
+  const setGovernor = (policy: FrequencyPolicy, governor: string) => {
+    invoke("set_cpu_governor", { governor, policy: policy.id })
+      .then(() => setError(null))
+      .catch((reason: unknown) => setError(toCommandError(reason)));
+  };
+
+  return (
+    <div className="flex flex-col gap-2 text-sm">
+      {boost !== null && (
+        <p className="text-muted-foreground">
+          Boost {boost ? "enabled" : "disabled"}
+        </p>
+      )}
+      {error && (
+        <p className="text-xs text-destructive">
+          {typeof error === "string" ? error : error.message}
+        </p>
+      )}
+      <ul className="flex flex-col gap-1">
+        {policies.map((policy) => {
+          const scaling = scalingOf(policy);
+          const limits = [policy.min_frequency, policy.max_frequency]
+            .map((mhz) => (mhz === null ? "?" : formatFrequency(mhz)))
+            .join(" – ");
+          return (
+            <li
+              key={policy.id}
+              className="flex items-center justify-between gap-2 border-b border-border/60 pb-1"
+            >
+              <span className="flex flex-col">
+                <span>
+                  CPU {policy.cpus.join(", ")}
+                  <span className="text-muted-foreground">
+                    {" "}
+                    · {policy.driver ?? "unknown driver"}
+                  </span>
+                </span>
+                <span className="stat-figure text-xs text-muted-foreground">
+                  {limits}
+                  {policy.base_frequency !== null &&
+                    ` · base ${formatFrequency(policy.base_frequency)}`}
+                </span>
+              </span>
+              <DropdownMenu>
+                <DropdownMenuTrigger asChild>
+                  <Button
+                    variant="outline"
+                    size="sm"
+                    className="h-7 gap-1 px-2 font-mono text-xs"
+                    disabled={policy.available_governors.length === 0}
+                  >
+                    {scaling?.governor ?? "…"}
+                    <IconChevronDown className="size-3" />
+                  </Button>
+                </DropdownMenuTrigger>
+                <DropdownMenuContent align="end">
+                  <DropdownMenuLabel>Governor</DropdownMenuLabel>
+                  <DropdownMenuRadioGroup
+                    value={scaling?.governor ?? ""}
+                    onValueChange={(governor) => setGovernor(policy, governor)}
+                  >
+                    {policy.available_governors.map((governor) => (
+                      <DropdownMenuRadioItem
+                        key={governor}
+                        value={governor}
+                        className="font-mono text-xs"
+                      >
+                        {governor}
+                      </DropdownMenuRadioItem>
+                    ))}
+                  </DropdownMenuRadioGroup>
+                </DropdownMenuContent>
+              </DropdownMenu>
+            </li>
+          );
+        })}
+      </ul>
+    </div>
+  );
+}
@@ -17,9 +17,9 @@ const MetricsContext = createContext<MetricsState>({
 
 /**
  * Single app-level subscription to the backend `overview` stream, shared by
- * the dashboard and the status footer. Only one subscriber may exist per
- * stream name (starting a stream cancels its predecessor), so consumers MUST
- * read from this context instead of calling useStream("overview") themselves.
+ * the dashboard and the status footer. Read from this context rather than
+ * calling useStream("overview") again: the backend would happily serve a
+ * second subscriber, but this one also owns the rolling history.
  */
 export function MetricsProvider({ children }: { children: React.ReactNode }) {
   const [latest, setLatest] = useState<OverviewSnapshot | null>(null);
@@ -0,0 +1,63 @@
+import { useCallback, useEffect, useRef, useState } from "react";
+
+import { useStream } from "@/hooks/use-stream";
+import type { ProcessInfo, ProcessesMessage } from "@/types/system";
+
+/** Seconds `process` had been running as of `timestamp` (unix ms). */
+export function runTime(process: ProcessInfo, timestamp: number) {
+  return Math.max(0, Math.floor(timestamp / 1000) - process.start_time);
+}
+
+/**
+ * Subscribes to the delta-encoded `processes` stream and maintains the full
+ * process table, along with the timestamp of the message it reflects. A gap
+ * in sequence numbers drops deltas until the resync snapshot arrives, so
+ * the table never drifts from the backend.
+ */
+export function useProcessStream(enabled = true) {
+  const [state, setState] = useState<{
+    processes: ProcessInfo[];
+    timestamp: number;
+  }>({ processes: [], timestamp: 0 });
+  const table = useRef(new Map<number, ProcessInfo>());
+  // Sequence number of the last applied message; null while awaiting a
+  // snapshot (initially, and after requesting a resync).
+  const lastSeq = useRef<number | null>(null);
+  const requestResync = useRef<() => void>(() => {});
+
+  const resync = useStream<ProcessesMessage>(
+    "processes",
+    useCallback((message) => {
+      if (message.kind === "snapshot") {
+        table.current = new Map(message.processes.map((p) => [p.pid, p]));
+      } else {
+        if (lastSeq.current === null) return;
+        if (message.seq !== lastSeq.current + 1) {
+          lastSeq.current = null;
+          requestResync.current();
+          return;
+        }
+        const next = new Map(table.current);
+        for (const pid of message.removed) next.delete(pid);
+        for (const process of message.added) next.set(process.pid, process);
+        for (const change of message.changed) {
+          const current = next.get(change.pid);
+          if (current) next.set(change.pid, { ...current, ...change });
+        }
+        table.current = next;
+      }
+      lastSeq.current = message.seq;
+      setState({
+        processes: [...table.current.values()],
+        timestamp: message.timestamp,
+      });
+    }, []),
+    enabled,
+  );
+
+  useEffect(() => {
+    requestResync.current = resync;
+  }, [resync]);
+
+  return state;
+}
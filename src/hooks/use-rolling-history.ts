@@ -3,6 +3,8 @@ import { useCallback, useState } from "react";
 /**
  * Bounded sample buffer for rolling chart windows. Appending past capacity
  * drops the oldest sample, so chart state can never grow unbounded.
+ * `prepend` slots older samples (e.g. a backend backfill) in front of
+ * whatever has streamed in meanwhile.
  */
 export function useRollingHistory<T>(capacity = 60) {
   const [samples, setSamples] = useState<T[]>([]);
@@ -14,5 +16,12 @@ export function useRollingHistory<T>(capacity = 60) {
     [capacity],
   );
 
-  return [samples, push] as const;
+  const prepend = useCallback(
+    (older: T[]) => {
+      setSamples((prev) => [...older, ...prev].slice(-capacity));
+    },
+    [capacity],
+  );
+
+  return [samples, push, prepend] as const;
 }
@@ -1,5 +1,5 @@
 import { Channel, invoke } from "@tauri-apps/api/core";
-import { useEffect, useRef } from "react";
+import { useCallback, useEffect, useRef } from "react";
 
 import type { StreamName } from "@/types/system";
 
@@ -10,21 +10,36 @@ const STREAM_COMMANDS: Record<StreamName, string> = {
   overview: "get_overview_info",
   disks: "get_disks_info",
   networks: "get_network_info",
+  sensors: "get_sensors_info",
+  load: "get_load_info",
+  interrupts: "get_interrupts_info",
 };
 
 /**
  * Subscribes to a backend sampling stream for the lifetime of the component.
  *
- * The backend cancels any previous stream with the same name when a new one
- * starts, and the returned cleanup stops sampling on unmount, so remounts
- * (including StrictMode double-mounts) can never leak a sampling loop.
+ * Each mount subscribes under its own token, so any number of components
+ * (or windows) can consume the same stream; the backend shares one sampler
+ * between them. The returned cleanup stops only this subscription, so
+ * remounts (including StrictMode double-mounts) can never leak one.
+ *
+ * `intervalMs` overrides the sampling period (the backend clamps it and
+ * defaults to 1 s); changing it restarts the stream, as does changing
+ * `args`, extra arguments for the stream's command (e.g. `idleStates` for
+ * the CPU stream). The returned `resync`
+ * asks a delta-encoded stream for a full snapshot on its next tick.
  */
 export function useStream<T>(
   stream: StreamName,
   onData: (data: T) => void,
   enabled = true,
+  intervalMs?: number,
+  args?: Record<string, unknown>,
 ) {
   const handler = useRef(onData);
+  // Compared by value so callers can pass an inline object.
+  const argsKey = JSON.stringify(args ?? {});
+  const requestResync = useRef<() => void>(() => {});
 
   useEffect(() => {
     handler.current = onData;
@@ -33,16 +48,26 @@ export function useStream<T>(
   useEffect(() => {
     if (!enabled) return;
 
+    const token = crypto.randomUUID();
     const channel = new Channel<T>();
+    requestResync.current = () =>
+      // Rejects with `stream_cancelled` if this subscription already ended,
+      // in which case there is nothing left to resync.
+      void invoke("resync_stream", { stream, token }).catch(() => {});
     channel.onmessage = (data) => handler.current(data);
-    invoke(STREAM_COMMANDS[stream], { onEvent: channel }).catch(
-      (error: unknown) => {
-        console.error(`stream "${stream}" failed`, error);
-      },
-    );
+    invoke(STREAM_COMMANDS[stream], {
+      ...(JSON.parse(argsKey) as Record<string, unknown>),
+      onEvent: channel,
+      token,
+      intervalMs,
+    }).catch((error: unknown) => {
+      console.error(`stream "${stream}" failed`, error);
+    });
 
     return () => {
-      void invoke("stop_stream", { stream });
+      void invoke("stop_stream", { stream, token });
     };
-  }, [stream, enabled]);
+  }, [stream, enabled, intervalMs, argsKey]);
+
+  return useCallback(() => requestResync.current(), []);
 }
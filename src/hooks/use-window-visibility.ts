@@ -0,0 +1,20 @@
+import { invoke } from "@tauri-apps/api/core";
+import { useEffect } from "react";
+
+/**
+ * Reports the webview's visibility to the backend, which backs off this
+ * window's streams while it is hidden. The backend already notices a
+ * minimised window on its own; this also covers one that is fully covered
+ * by other windows, where the webview is the only thing that knows.
+ */
+export function useWindowVisibility() {
+  useEffect(() => {
+    const report = () =>
+      void invoke("set_window_occluded", {
+        occluded: document.visibilityState === "hidden",
+      });
+    report();
+    document.addEventListener("visibilitychange", report);
+    return () => document.removeEventListener("visibilitychange", report);
+  }, []);
+}
@@ -0,0 +1,18 @@
+import type { CommandError } from "@/types/system";
+
+/**
+ * Normalises a rejected `invoke`. Our own commands reject with a
+ * `CommandError`; anything else (e.g. Tauri failing to deserialise the
+ * arguments) arrives as a plain string and is reported as-is.
+ */
+export function toCommandError(error: unknown): CommandError | string {
+  if (
+    typeof error === "object" &&
+    error !== null &&
+    "kind" in error &&
+    "message" in error
+  ) {
+    return error as CommandError;
+  }
+  return String(error);
+}
@@ -15,6 +15,14 @@ export function formatRate(bytesPerSecond: number): string {
   return `${formatBytes(bytesPerSecond)}/s`;
 }
 
+/** Events per second, e.g. "12.4k/s". */
+export function formatEventRate(perSecond: number): string {
+  if (!Number.isFinite(perSecond) || perSecond < 0) return "—";
+  if (perSecond >= 1e6) return `${(perSecond / 1e6).toFixed(1)}M/s`;
+  if (perSecond >= 1e3) return `${(perSecond / 1e3).toFixed(1)}k/s`;
+  return `${Math.round(perSecond)}/s`;
+}
+
 export function formatPercent(value: number, decimals = 1): string {
   if (!Number.isFinite(value)) return "—";
   return `${value.toFixed(decimals)}%`;
@@ -0,0 +1,31 @@
+import { invoke } from "@tauri-apps/api/core";
+
+import type { HistoryMetric, HistorySeries } from "@/types/system";
+
+/**
+ * Recent backend history for one metric, for backfilling rolling charts.
+ * Process metrics are only in the persistent metric store.
+ */
+export function getHistory(
+  metric: Exclude<HistoryMetric, "process_cpu" | "process_memory">,
+  seconds = 60,
+) {
+  return invoke<HistorySeries[]>("get_history", { metric, seconds });
+}
+
+/** Every timestamp present in any series, oldest first. */
+export function timestampsOf(series: HistorySeries[]): number[] {
+  const all = new Set<number>();
+  for (const s of series) {
+    for (const point of s.points) all.add(point.timestamp);
+  }
+  return [...all].sort((a, b) => a - b);
+}
+
+/** Looks up a series value by timestamp; missing points read as 0. */
+export function valueLookup(series: HistorySeries) {
+  const byTimestamp = new Map(
+    series.points.map((point) => [point.timestamp, point.value]),
+  );
+  return (timestamp: number) => byTimestamp.get(timestamp) ?? 0;
+}
@@ -0,0 +1,32 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Starts recording what the running streams read, at their own rates, to
+ * the session file `name` or to a new, timestamped one. Resolves to the
+ * file name used.
+ */
+export function startRecording(name?: string) {
+  return invoke<string>("start_recording", { name });
+}
+
+export function stopRecording() {
+  return invoke<void>("stop_recording");
+}
+
+/**
+ * Feeds every stream from the session file `name` instead of the live
+ * system, `speed` times faster than real time. Resolves to its length in
+ * ms.
+ */
+export function startReplay(name: string, speed = 1) {
+  return invoke<number>("start_replay", { name, speed });
+}
+
+export function stopReplay() {
+  return invoke<void>("stop_replay");
+}
+
+/** Jumps the running replay to `offsetMs` into the recording. */
+export function seekReplay(offsetMs: number) {
+  return invoke<void>("seek_replay", { offsetMs });
+}
@@ -2,6 +2,8 @@ import { invoke } from "@tauri-apps/api/core";
 import { useCallback, useEffect, useMemo, useState } from "react";
 
 import { CoreHeatmap } from "@/components/core-heatmap";
+import { CpuTimesBar } from "@/components/cpu-times-bar";
+import { FrequencyPolicies } from "@/components/frequency-policies";
 import { UsageChart, type UsagePoint } from "@/components/usage-chart";
 import {
   Accordion,
@@ -14,14 +16,79 @@ import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { useRollingHistory } from "@/hooks/use-rolling-history";
 import { useStream } from "@/hooks/use-stream";
 import { formatBytes, formatFrequency, formatPercent } from "@/lib/format";
-import type { CpuSnapshot, CpuStatic } from "@/types/system";
+import { getHistory, timestampsOf, valueLookup } from "@/lib/history";
+import { cn } from "@/lib/utils";
+import type {
+  CacheInfo,
+  CoreSnapshot,
+  CpuSnapshot,
+  CpuStatic,
+  FeatureCategory,
+  TlbInfo,
+  VulnerabilityStatus,
+} from "@/types/system";
 
 const CPU_COLOR = "var(--chart-1)";
 
+const STATUS_STYLES: Record<VulnerabilityStatus, string> = {
+  not_affected: "text-muted-foreground",
+  mitigated: "text-foreground",
+  vulnerable: "font-semibold text-destructive",
+  unknown: "text-muted-foreground italic",
+};
+
+const FEATURE_CATEGORIES: [FeatureCategory, string][] = [
+  ["simd", "SIMD"],
+  ["crypto", "Crypto"],
+  ["virtualization", "Virtualization"],
+  ["security", "Security"],
+  ["memory", "Memory"],
+  ["other", "Other"],
+];
+
+const hex = (value: number) => `0x${value.toString(16)}`;
+
+const associativityOf = (ways: number | null, fullyAssociative: boolean) => {
+  if (fullyAssociative) return "fully associative";
+  return ways === null ? null : `${ways}-way`;
+};
+
+const describeCache = (cache: CacheInfo) =>
+  [
+    associativityOf(cache.associativity, cache.fully_associative),
+    cache.line_size !== null && `${cache.line_size} B lines`,
+    cache.sets !== null && `${cache.sets} sets`,
+    cache.partitions !== null &&
+      cache.partitions > 1 &&
+      `${cache.partitions} partitions`,
+    `shared by ${cache.shared_by}`,
+    cache.inclusive !== null &&
+      (cache.inclusive ? "inclusive" : "non-inclusive"),
+  ]
+    .filter(Boolean)
+    .join(" · ");
+
+const describeTlb = (tlb: TlbInfo) =>
+  [
+    tlb.entries !== null && `${tlb.entries} entries`,
+    associativityOf(tlb.associativity, tlb.fully_associative),
+    tlb.page_sizes.length > 0 && tlb.page_sizes.join(", "),
+    tlb.shared_by !== null && `shared by ${tlb.shared_by}`,
+  ]
+    .filter(Boolean)
+    .join(" · ");
+
+/** The part of a snapshot the charts use; backfill has no frequency. */
+type CpuSample = {
+  global_usage: number;
+  cores: Pick<CoreSnapshot, "name" | "usage">[];
+};
+
 export const CPU = () => {
   const [statics, setStatics] = useState<CpuStatic | null>(null);
   const [latest, setLatest] = useState<CpuSnapshot | null>(null);
-  const [history, pushHistory] = useRollingHistory<CpuSnapshot>(60);
+  const [history, pushHistory, prependHistory] =
+    useRollingHistory<CpuSample>(60);
   const [view, setView] = useState<"overall" | "cores">("overall");
 
   useEffect(() => {
@@ -30,6 +97,27 @@ export const CPU = () => {
       .catch((error: unknown) => console.error("get_cpu_static failed", error));
   }, []);
 
+  useEffect(() => {
+    Promise.all([getHistory("cpu"), getHistory("cpu_cores")])
+      .then(([[global], cores]) => {
+        if (!global) return;
+        const usageOf = valueLookup(global);
+        const coreLookups = cores.map(
+          (core) => [core.name, valueLookup(core)] as const,
+        );
+        prependHistory(
+          timestampsOf([global]).map((timestamp) => ({
+            global_usage: usageOf(timestamp),
+            cores: coreLookups.map(([name, lookup]) => ({
+              name,
+              usage: lookup(timestamp),
+            })),
+          })),
+        );
+      })
+      .catch((error: unknown) => console.error("get_history failed", error));
+  }, [prependHistory]);
+
   useStream<CpuSnapshot>(
     "cpu",
     useCallback(
@@ -39,6 +127,10 @@ export const CPU = () => {
       },
       [pushHistory],
     ),
+    true,
+    undefined,
+    // Only the per-core cards show them.
+    { idleStates: view === "cores" },
   );
 
   const overallPoints: UsagePoint[] = useMemo(
@@ -93,6 +185,11 @@ export const CPU = () => {
                   {statics.physical_cores} physical / {statics.logical_cores}{" "}
                   logical cores
                 </span>
+                {statics.core_names.length > 0 && (
+                  <span className="rounded-md bg-muted px-2 py-1">
+                    {statics.core_names.join(" + ")}
+                  </span>
+                )}
                 {averageFrequency !== null && (
                   <span className="stat-figure rounded-md bg-muted px-2 py-1">
                     {formatFrequency(averageFrequency)} avg
@@ -127,12 +224,22 @@ export const CPU = () => {
         </CardHeader>
         <CardContent>
           {view === "overall" ? (
-            <UsageChart points={overallPoints} color={CPU_COLOR} height={300} />
+            <div className="flex flex-col gap-4">
+              <UsageChart
+                points={overallPoints}
+                color={CPU_COLOR}
+                height={300}
+              />
+              {latest?.global_times && (
+                <CpuTimesBar times={latest.global_times} legend />
+              )}
+            </div>
           ) : (
             <div className="flex flex-col gap-4">
               <CoreHeatmap
                 usages={latest?.cores.map((core) => core.usage) ?? []}
                 variant="cells"
+                topology={statics?.topology}
               />
               <div className="grid grid-cols-1 gap-4 sm:grid-cols-2 xl:grid-cols-3">
                 {corePoints.map(([name, points]) => {
@@ -156,6 +263,25 @@ export const CPU = () => {
                         height={140}
                         compact
                       />
+                      {core?.times && (
+                        <div className="px-1 pt-2">
+                          <CpuTimesBar times={core.times} />
+                        </div>
+                      )}
+                      {core?.idle_states && core.idle_states.length > 0 && (
+                        <p className="stat-figure px-1 pt-1 text-[11px] text-muted-foreground">
+                          {core.idle_states.map((state, index) => (
+                            <span
+                              key={state.name}
+                              title={`Exit latency ${state.latency} µs${state.disabled ? ", disabled" : ""}`}
+                              className={cn(state.disabled && "line-through")}
+                            >
+                              {index > 0 && " · "}
+                              {state.name} {formatPercent(state.residency, 0)}
+                            </span>
+                          ))}
+                        </p>
+                      )}
                     </div>
                   );
                 })}
@@ -166,28 +292,107 @@ export const CPU = () => {
       </Card>
 
       {statics &&
-        (statics.cache_sizes.length > 0 || statics.features.length > 0) && (
+        (statics.cache_sizes.length > 0 ||
+          statics.tlbs.length > 0 ||
+          statics.features.length > 0 ||
+          statics.frequency_policies.length > 0 ||
+          statics.signature !== null ||
+          statics.vulnerabilities.length > 0) && (
           <Card>
             <CardHeader>
               <CardTitle className="text-base">Processor details</CardTitle>
             </CardHeader>
             <CardContent>
               <Accordion type="single" collapsible defaultValue="cache">
-                {statics.cache_sizes.length > 0 && (
+                {(statics.cache_sizes.length > 0 ||
+                  statics.tlbs.length > 0) && (
                   <AccordionItem value="cache">
                     <AccordionTrigger>Cache</AccordionTrigger>
-                    <AccordionContent>
+                    <AccordionContent className="flex flex-col gap-3">
                       <ul className="flex flex-col gap-1">
                         {statics.cache_sizes.map((cache) => (
                           <li
                             key={cache.label}
-                            className="flex items-baseline justify-between border-b border-border/60 pb-1 text-sm"
+                            className="flex flex-col border-b border-border/60 pb-1 text-sm"
+                          >
+                            <div className="flex items-baseline justify-between">
+                              <span className="text-muted-foreground">
+                                {cache.label}
+                              </span>
+                              <span className="stat-figure">
+                                {formatBytes(cache.bytes, 0)}
+                              </span>
+                            </div>
+                            <span className="stat-figure text-xs text-muted-foreground">
+                              {describeCache(cache)}
+                            </span>
+                          </li>
+                        ))}
+                      </ul>
+                      {statics.tlbs.length > 0 && (
+                        <div className="flex flex-col gap-1">
+                          <span className="text-xs font-medium">TLBs</span>
+                          <ul className="flex flex-col gap-1">
+                            {statics.tlbs.map((tlb, index) => (
+                              <li
+                                key={`${tlb.label}-${index}`}
+                                className="flex items-baseline justify-between gap-4 border-b border-border/60 pb-1 text-sm"
+                              >
+                                <span className="text-muted-foreground">
+                                  {tlb.label}
+                                </span>
+                                <span className="stat-figure text-right text-xs text-muted-foreground">
+                                  {describeTlb(tlb)}
+                                </span>
+                              </li>
+                            ))}
+                          </ul>
+                        </div>
+                      )}
+                    </AccordionContent>
+                  </AccordionItem>
+                )}
+                {statics.frequency_policies.length > 0 && (
+                  <AccordionItem value="frequency">
+                    <AccordionTrigger>Frequency scaling</AccordionTrigger>
+                    <AccordionContent>
+                      <FrequencyPolicies
+                        policies={statics.frequency_policies}
+                        boost={statics.boost}
+                        cores={latest?.cores ?? []}
+                      />
+                    </AccordionContent>
+                  </AccordionItem>
+                )}
+                {(statics.signature !== null ||
+                  statics.vulnerabilities.length > 0) && (
+                  <AccordionItem value="security">
+                    <AccordionTrigger>
+                      Microcode and vulnerabilities
+                    </AccordionTrigger>
+                    <AccordionContent className="flex flex-col gap-3">
+                      <p className="stat-figure text-sm text-muted-foreground">
+                        {statics.signature &&
+                          `${statics.signature.vendor} family ${hex(statics.signature.family)} model ${hex(statics.signature.model)} stepping ${statics.signature.stepping}`}
+                        {statics.microcode &&
+                          ` · microcode ${statics.microcode}`}
+                      </p>
+                      <ul className="flex flex-col gap-1">
+                        {statics.vulnerabilities.map((vulnerability) => (
+                          <li
+                            key={vulnerability.name}
+                            className="flex items-baseline justify-between gap-4 border-b border-border/60 pb-1 text-sm"
                           >
-                            <span className="text-muted-foreground">
-                              {cache.label}
+                            <span className="font-mono text-xs">
+                              {vulnerability.name}
                             </span>
-                            <span className="stat-figure">
-                              {formatBytes(cache.bytes, 0)}
+                            <span
+                              className={cn(
+                                "text-right text-xs",
+                                STATUS_STYLES[vulnerability.status],
+                              )}
+                            >
+                              {vulnerability.detail}
                             </span>
                           </li>
                         ))}
@@ -200,17 +405,37 @@ export const CPU = () => {
                     <AccordionTrigger>
                       Instruction set features
                     </AccordionTrigger>
-                    <AccordionContent>
-                      <div className="flex flex-wrap gap-1.5">
-                        {statics.features.map((feature) => (
-                          <span
-                            key={feature}
-                            className="rounded bg-muted px-1.5 py-0.5 font-mono text-xs text-muted-foreground"
-                          >
-                            {feature}
-                          </span>
-                        ))}
-                      </div>
+                    <AccordionContent className="flex flex-col gap-3">
+                      {FEATURE_CATEGORIES.map(([category, label]) => {
+                        const features = statics.features.filter(
+                          (feature) => feature.category === category,
+                        );
+                        if (features.length === 0) return null;
+                        return (
+                          <div key={category} className="flex flex-col gap-1">
+                            <span className="text-xs font-medium">{label}</span>
+                            <div className="flex flex-wrap gap-1.5">
+                              {features.map((feature) => (
+                                <span
+                                  key={feature.name}
+                                  title={
+                                    feature.usable
+                                      ? undefined
+                                      : "Supported by the CPU but not enabled by the OS"
+                                  }
+                                  className={cn(
+                                    "rounded bg-muted px-1.5 py-0.5 font-mono text-xs text-muted-foreground",
+                                    !feature.usable &&
+                                      "text-destructive line-through",
+                                  )}
+                                >
+                                  {feature.name}
+                                </span>
+                              ))}
+                            </div>
+                          </div>
+                        );
+                      })}
                     </AccordionContent>
                   </AccordionItem>
                 )}
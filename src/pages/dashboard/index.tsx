@@ -5,7 +5,7 @@ import {
   IconFileDownload,
 } from "@tabler/icons-react";
 import { invoke } from "@tauri-apps/api/core";
-import { useEffect, useState } from "react";
+import { useCallback, useEffect, useState } from "react";
 
 import { CoreHeatmap } from "@/components/core-heatmap";
 import { useMetrics } from "@/components/metrics-provider";
@@ -19,7 +19,32 @@ import {
   formatRate,
 } from "@/lib/format";
 import { cn } from "@/lib/utils";
-import type { DiskInfo, SystemInfo } from "@/types/system";
+import type {
+  CgroupLimits,
+  DiskInfo,
+  DisksSnapshot,
+  Environment,
+  PressureReading,
+  SystemInfo,
+} from "@/types/system";
+
+const PRESSURE_RESOURCES: [keyof PressureReading, string][] = [
+  ["cpu", "CPU"],
+  ["memory", "Memory"],
+  ["io", "I/O"],
+];
+
+/** e.g. "docker on KVM". */
+const describeEnvironment = ({ hypervisor, container }: Environment) =>
+  [container, hypervisor ?? "bare metal"].filter(Boolean).join(" on ");
+
+const describeLimits = ({ cpu_quota, memory_limit }: CgroupLimits) =>
+  [
+    cpu_quota !== null && `${cpu_quota.toFixed(1)} CPUs`,
+    memory_limit !== null && formatBytes(memory_limit),
+  ]
+    .filter(Boolean)
+    .join(" · ");
 
 function Panel({
   title,
@@ -58,7 +83,10 @@ export const Dashboard = () => {
   const [systemInfo, setSystemInfo] = useState<SystemInfo | null>(null);
   const [uptime, setUptime] = useState<number | null>(null);
 
-  useStream<DiskInfo[]>("disks", setDisks);
+  useStream<DisksSnapshot>(
+    "disks",
+    useCallback((snapshot) => setDisks(snapshot.disks), []),
+  );
 
   useEffect(() => {
     invoke<SystemInfo>("get_system_info")
@@ -89,6 +117,8 @@ export const Dashboard = () => {
       ? (latest.mem_used / latest.mem_total) * 100
       : null;
 
+  const limits = systemInfo && describeLimits(systemInfo.environment.limits);
+
   return (
     <div className="grid grid-cols-12 gap-2">
       <Panel
@@ -237,6 +267,40 @@ export const Dashboard = () => {
         </table>
       </Panel>
 
+      {latest && (
+        <Panel
+          title="Load"
+          value={[
+            latest.load_average.one,
+            latest.load_average.five,
+            latest.load_average.fifteen,
+          ]
+            .map((load) => load.toFixed(2))
+            .join(" · ")}
+          className="col-span-12"
+        >
+          <div className="grid grid-cols-1 gap-x-6 gap-y-2 sm:grid-cols-3">
+            {PRESSURE_RESOURCES.map(([resource, label]) => {
+              const pressure = latest.pressure[resource];
+              if (!pressure) return null;
+              const full = pressure.full
+                ? ` · full ${formatPercent(pressure.full.avg10)}`
+                : "";
+              return (
+                <StatMeter
+                  key={resource}
+                  label={`${label} stalled (10s)`}
+                  display={`some ${formatPercent(pressure.some.avg10)}${full}`}
+                  value={pressure.some.avg10}
+                  max={100}
+                  color="var(--chart-5)"
+                />
+              );
+            })}
+          </div>
+        </Panel>
+      )}
+
       <Panel title="System" className="col-span-12">
         <dl className="grid grid-cols-2 gap-x-6 gap-y-1 text-xs sm:grid-cols-4">
           {(
@@ -250,6 +314,13 @@ export const Dashboard = () => {
               ],
               ["Kernel", systemInfo?.os_kernel_version],
               ["Uptime", uptime === null ? undefined : formatDuration(uptime)],
+              [
+                "Runs on",
+                systemInfo
+                  ? describeEnvironment(systemInfo.environment)
+                  : undefined,
+              ],
+              ...(limits ? [["Limits", limits]] : []),
             ] as [string, string | undefined][]
           ).map(([label, value]) => (
             <div
@@ -1,16 +1,19 @@
 import { IconDatabase, IconUsb } from "@tabler/icons-react";
-import { useState } from "react";
+import { useCallback, useState } from "react";
 
 import { StatMeter } from "@/components/stat-meter";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { useStream } from "@/hooks/use-stream";
 import { formatBytes, formatRate } from "@/lib/format";
-import type { DiskInfo } from "@/types/system";
+import type { DiskInfo, DisksSnapshot } from "@/types/system";
 
 export const Disks = () => {
   const [disks, setDisks] = useState<DiskInfo[]>([]);
 
-  useStream<DiskInfo[]>("disks", setDisks);
+  useStream<DisksSnapshot>(
+    "disks",
+    useCallback((snapshot) => setDisks(snapshot.disks), []),
+  );
 
   return (
     <div className="flex flex-col gap-3">
@@ -0,0 +1,157 @@
+import { useCallback, useMemo, useState } from "react";
+
+import { CoreHeatmap } from "@/components/core-heatmap";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { useStream } from "@/hooks/use-stream";
+import { formatEventRate, formatPercent } from "@/lib/format";
+import type { InterruptsSnapshot, IrqRate } from "@/types/system";
+
+const TOP_IRQS = 20;
+
+/** The CPU taking most of a row's events, as "cpu3 · 92%". */
+function busiestCpu(row: IrqRate, cpus: number[]): string | null {
+  if (row.per_cpu.length !== cpus.length || row.total === 0) return null;
+  let busiest = 0;
+  row.per_cpu.forEach((rate, index) => {
+    if (rate > row.per_cpu[busiest]) busiest = index;
+  });
+  const share = (row.per_cpu[busiest] / row.total) * 100;
+  return `cpu${cpus[busiest]} · ${formatPercent(share, 0)}`;
+}
+
+function RateTable({
+  rows,
+  cpus,
+  showDevice,
+}: {
+  rows: IrqRate[];
+  cpus: number[];
+  showDevice?: boolean;
+}) {
+  return (
+    <table className="w-full text-xs">
+      <tbody>
+        {rows.map((row) => (
+          <tr
+            key={row.irq}
+            className="border-b border-border/40 last:border-0"
+          >
+            <td className="py-1 pr-2 font-mono">{row.irq}</td>
+            {showDevice && (
+              <td
+                className="max-w-[280px] truncate py-1 pr-2 text-muted-foreground"
+                title={row.device}
+              >
+                {row.device}
+              </td>
+            )}
+            <td className="stat-figure py-1 pr-2 text-right text-muted-foreground">
+              {busiestCpu(row, cpus)}
+            </td>
+            <td className="stat-figure py-1 text-right font-semibold">
+              {formatEventRate(row.total)}
+            </td>
+          </tr>
+        ))}
+      </tbody>
+    </table>
+  );
+}
+
+export const Interrupts = () => {
+  const [latest, setLatest] = useState<InterruptsSnapshot | null>(null);
+
+  useStream<InterruptsSnapshot>(
+    "interrupts",
+    useCallback((snapshot) => setLatest(snapshot), []),
+  );
+
+  /** Each CPU's share of all IRQs, indexed by CPU id like the heatmap. */
+  const shares = useMemo(() => {
+    if (!latest || latest.cpus.length === 0) return [];
+    const totals = new Array<number>(Math.max(...latest.cpus) + 1).fill(0);
+    for (const irq of latest.irqs) {
+      if (irq.per_cpu.length !== latest.cpus.length) continue;
+      irq.per_cpu.forEach((rate, index) => {
+        totals[latest.cpus[index]] += rate;
+      });
+    }
+    const sum = totals.reduce((total, rate) => total + rate, 0);
+    return totals.map((rate) => (sum > 0 ? (rate / sum) * 100 : 0));
+  }, [latest]);
+
+  const totals: [string, number | undefined][] = [
+    ["Context switches", latest?.context_switches],
+    ["Interrupts", latest?.interrupts],
+    ["Forks", latest?.forks],
+  ];
+
+  return (
+    <div className="flex flex-col gap-3">
+      <header className="flex flex-col gap-1">
+        <h1 className="text-lg font-semibold">Interrupts</h1>
+        <p className="text-sm text-muted-foreground">
+          Hardware and software interrupt rates, and how they spread across
+          cores.
+        </p>
+      </header>
+
+      <div className="grid grid-cols-1 gap-3 sm:grid-cols-3">
+        {totals.map(([label, value]) => (
+          <Card key={label}>
+            <CardHeader className="pb-1">
+              <CardTitle className="text-sm text-muted-foreground">
+                {label}
+              </CardTitle>
+            </CardHeader>
+            <CardContent className="stat-figure text-2xl font-semibold">
+              {value === undefined ? "…" : formatEventRate(value)}
+            </CardContent>
+          </Card>
+        ))}
+      </div>
+
+      {latest && latest.cpus.length === 0 && (
+        <p className="text-sm text-muted-foreground">
+          Interrupt counters are only available on Linux.
+        </p>
+      )}
+
+      {shares.length > 0 && (
+        <Card>
+          <CardHeader className="pb-3">
+            <CardTitle className="text-sm">Share of IRQs by core</CardTitle>
+          </CardHeader>
+          <CardContent>
+            <CoreHeatmap usages={shares} variant="cells" />
+          </CardContent>
+        </Card>
+      )}
+
+      {latest && latest.cpus.length > 0 && (
+        <div className="grid grid-cols-1 gap-3 lg:grid-cols-3">
+          <Card className="lg:col-span-2">
+            <CardHeader className="pb-3">
+              <CardTitle className="text-sm">Busiest IRQs</CardTitle>
+            </CardHeader>
+            <CardContent>
+              <RateTable
+                rows={latest.irqs.slice(0, TOP_IRQS)}
+                cpus={latest.cpus}
+                showDevice
+              />
+            </CardContent>
+          </Card>
+          <Card>
+            <CardHeader className="pb-3">
+              <CardTitle className="text-sm">Softirqs</CardTitle>
+            </CardHeader>
+            <CardContent>
+              <RateTable rows={latest.softirqs} cpus={latest.cpus} />
+            </CardContent>
+          </Card>
+        </div>
+      )}
+    </div>
+  );
+};
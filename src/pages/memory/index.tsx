@@ -1,4 +1,4 @@
-import { useCallback, useState } from "react";
+import { useCallback, useEffect, useState } from "react";
 
 import { StatMeter } from "@/components/stat-meter";
 import { UsageChart, type UsagePoint } from "@/components/usage-chart";
@@ -6,6 +6,7 @@ import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { useRollingHistory } from "@/hooks/use-rolling-history";
 import { useStream } from "@/hooks/use-stream";
 import { formatBytes, formatPercent } from "@/lib/format";
+import { getHistory } from "@/lib/history";
 import type { MemorySnapshot } from "@/types/system";
 
 const MEMORY_COLOR = "var(--chart-2)";
@@ -13,7 +14,16 @@ const SWAP_COLOR = "var(--chart-4)";
 
 const Memory = () => {
   const [latest, setLatest] = useState<MemorySnapshot | null>(null);
-  const [history, pushHistory] = useRollingHistory<number>(60);
+  const [history, pushHistory, prependHistory] =
+    useRollingHistory<number>(60);
+
+  useEffect(() => {
+    getHistory("memory")
+      .then(([series]) => {
+        if (series) prependHistory(series.points.map((point) => point.value));
+      })
+      .catch((error: unknown) => console.error("get_history failed", error));
+  }, [prependHistory]);
 
   useStream<MemorySnapshot>(
     "memory",
@@ -1,23 +1,56 @@
 import { IconArrowDown, IconArrowUp } from "@tabler/icons-react";
-import { useCallback, useMemo, useState } from "react";
+import { useCallback, useEffect, useMemo, useState } from "react";
 
 import { Sparkline } from "@/components/sparkline";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { useRollingHistory } from "@/hooks/use-rolling-history";
 import { useStream } from "@/hooks/use-stream";
 import { formatBytes, formatRate } from "@/lib/format";
-import type { NetworkInfo } from "@/types/system";
+import { getHistory, timestampsOf, valueLookup } from "@/lib/history";
+import type { NetworkInfo, NetworksSnapshot } from "@/types/system";
+
+/** The part of a snapshot the sparklines use. */
+type RateSample = Pick<
+  NetworkInfo,
+  "name" | "received_rate" | "transmitted_rate"
+>[];
 
 export const Network = () => {
   const [interfaces, setInterfaces] = useState<NetworkInfo[]>([]);
-  const [history, pushHistory] = useRollingHistory<NetworkInfo[]>(60);
+  const [history, pushHistory, prependHistory] =
+    useRollingHistory<RateSample>(60);
+
+  useEffect(() => {
+    Promise.all([getHistory("network_rx"), getHistory("network_tx")])
+      .then(([rx, tx]) => {
+        const txByName = new Map(tx.map((series) => [series.name, series]));
+        const lookups = rx.map((series) => {
+          const txSeries = txByName.get(series.name);
+          return {
+            name: series.name,
+            rx: valueLookup(series),
+            tx: txSeries ? valueLookup(txSeries) : () => 0,
+          };
+        });
+        prependHistory(
+          timestampsOf(rx).map((timestamp) =>
+            lookups.map(({ name, rx, tx }) => ({
+              name,
+              received_rate: rx(timestamp),
+              transmitted_rate: tx(timestamp),
+            })),
+          ),
+        );
+      })
+      .catch((error: unknown) => console.error("get_history failed", error));
+  }, [prependHistory]);
 
-  useStream<NetworkInfo[]>(
+  useStream<NetworksSnapshot>(
     "networks",
     useCallback(
       (snapshot) => {
-        setInterfaces(snapshot);
-        pushHistory(snapshot);
+        setInterfaces(snapshot.interfaces);
+        pushHistory(snapshot.interfaces);
       },
       [pushHistory],
     ),
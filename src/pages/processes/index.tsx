@@ -44,10 +44,11 @@ import {
   TableHeader,
   TableRow,
 } from "@/components/ui/table";
-import { useStream } from "@/hooks/use-stream";
+import { runTime, useProcessStream } from "@/hooks/use-process-stream";
+import { toCommandError } from "@/lib/errors";
 import { formatBytes, formatDuration, formatPercent } from "@/lib/format";
 import { cn } from "@/lib/utils";
-import type { ProcessInfo } from "@/types/system";
+import type { CommandError, ProcessInfo } from "@/types/system";
 
 type SortKey = "pid" | "name" | "cpu_usage" | "memory";
 type SortDirection = "asc" | "desc";
@@ -58,7 +59,8 @@ type Column = {
   sortKey?: SortKey;
   headerClassName?: string;
   cellClassName?: string;
-  render: (process: ProcessInfo) => ReactNode;
+  /** `timestamp` is the unix ms the table reflects. */
+  render: (process: ProcessInfo, timestamp: number) => ReactNode;
 };
 
 const COLUMNS: Column[] = [
@@ -111,7 +113,7 @@ const COLUMNS: Column[] = [
     label: "Run time",
     headerClassName: "w-28",
     cellClassName: "stat-figure",
-    render: (p) => formatDuration(p.run_time),
+    render: (p, timestamp) => formatDuration(runTime(p, timestamp)),
   },
   {
     id: "parent",
@@ -161,8 +163,15 @@ function heatCellStyle(
   return undefined;
 }
 
+function describeKillError(error: CommandError | string): string {
+  if (typeof error === "string") return error;
+  if (error.kind === "permission_denied") {
+    return `${error.message}. It belongs to another user; run Monsoon with elevated privileges to manage it.`;
+  }
+  return error.message;
+}
+
 export const Processes = () => {
-  const [processes, setProcesses] = useState<ProcessInfo[]>([]);
   const [search, setSearch] = useState("");
   const [visibleColumns, setVisibleColumns] = useState<Set<string>>(
     () => new Set(DEFAULT_VISIBLE),
@@ -171,12 +180,14 @@ export const Processes = () => {
   const [sortDirection, setSortDirection] = useState<SortDirection>("desc");
   const [paused, setPaused] = useState(false);
   const [killTarget, setKillTarget] = useState<ProcessInfo | null>(null);
-  const [killError, setKillError] = useState<string | null>(null);
+  const [killError, setKillError] = useState<CommandError | string | null>(
+    null,
+  );
   // Snapshot of the row the user pressed, captured at pointer-down so a live
   // re-sort can't swap it out from under the click.
   const [selected, setSelected] = useState<ProcessInfo | null>(null);
 
-  useStream<ProcessInfo[]>("processes", setProcesses, !paused);
+  const { processes, timestamp } = useProcessStream(!paused);
 
   const deferredSearch = useDeferredValue(search);
 
@@ -239,7 +250,7 @@ export const Processes = () => {
       await invoke("kill_process", { pid: killTarget.pid, force });
       setKillError(null);
     } catch (error) {
-      setKillError(String(error));
+      setKillError(toCommandError(error));
     } finally {
       setKillTarget(null);
     }
@@ -263,7 +274,7 @@ export const Processes = () => {
 
       {killError && (
         <div className="flex items-center justify-between rounded-md border border-destructive/40 bg-destructive/10 px-3 py-2 text-sm text-destructive">
-          <span>{killError}</span>
+          <span>{describeKillError(killError)}</span>
           <Button
             variant="ghost"
             size="icon"
@@ -378,7 +389,7 @@ export const Processes = () => {
                         : undefined
                     }
                   >
-                    {column.render(process)}
+                    {column.render(process, timestamp)}
                   </TableCell>
                 ))}
                 <TableCell className="py-1.5 text-right">
@@ -468,6 +479,7 @@ export const Processes = () => {
           {liveSelected && (
             <ProcessDetail
               process={liveSelected}
+              timestamp={timestamp}
               ended={selectedEnded}
               processByPid={processByPid}
               onSelect={setSelected}
@@ -510,12 +522,14 @@ function DetailRow({ label, children }: { label: string; children: ReactNode })
 
 function ProcessDetail({
   process,
+  timestamp,
   ended,
   processByPid,
   onSelect,
   onKill,
 }: {
   process: ProcessInfo;
+  timestamp: number;
   ended: boolean;
   processByPid: Map<number, ProcessInfo>;
   onSelect: (process: ProcessInfo) => void;
@@ -548,7 +562,9 @@ function ProcessDetail({
         </DetailRow>
         <DetailRow label="Status">{process.status}</DetailRow>
         <DetailRow label="Run time">
-          <span className="stat-figure">{formatDuration(process.run_time)}</span>
+          <span className="stat-figure">
+            {formatDuration(runTime(process, timestamp))}
+          </span>
         </DetailRow>
         <DetailRow label="Executable">
           {process.exe ? (
@@ -0,0 +1,97 @@
+import { useCallback, useMemo, useState } from "react";
+
+import { StatMeter } from "@/components/stat-meter";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { useStream } from "@/hooks/use-stream";
+import type { SensorInfo, SensorKind, SensorsSnapshot } from "@/types/system";
+
+const GROUPS: { kind: SensorKind; title: string; color: string }[] = [
+  { kind: "temperature", title: "Temperatures", color: "var(--chart-5)" },
+  { kind: "fan", title: "Fans", color: "var(--chart-1)" },
+  { kind: "voltage", title: "Voltages", color: "var(--chart-4)" },
+];
+
+function formatReading(kind: SensorKind, value: number): string {
+  switch (kind) {
+    case "temperature":
+      return `${value.toFixed(1)} °C`;
+    case "fan":
+      return `${Math.round(value)} RPM`;
+    case "voltage":
+      return `${value.toFixed(3)} V`;
+  }
+}
+
+function describe(sensor: SensorInfo): string {
+  const limit = sensor.critical ?? sensor.max;
+  const value = formatReading(sensor.kind, sensor.value);
+  return limit === null
+    ? value
+    : `${value} / ${formatReading(sensor.kind, limit)}`;
+}
+
+export const Sensors = () => {
+  const [sensors, setSensors] = useState<SensorInfo[]>([]);
+
+  useStream<SensorsSnapshot>(
+    "sensors",
+    useCallback((snapshot) => setSensors(snapshot.sensors), []),
+  );
+
+  const groups = useMemo(
+    () =>
+      GROUPS.map((group) => ({
+        ...group,
+        sensors: sensors.filter((sensor) => sensor.kind === group.kind),
+      })).filter((group) => group.sensors.length > 0),
+    [sensors],
+  );
+
+  return (
+    <div className="flex flex-col gap-3">
+      <header className="flex flex-col gap-1">
+        <h1 className="text-lg font-semibold">Sensors</h1>
+        <p className="text-sm text-muted-foreground">
+          Temperatures, fan speeds and voltages against their limits.
+        </p>
+      </header>
+
+      {groups.length === 0 && (
+        <p className="text-sm text-muted-foreground">
+          No sensors reported. They may need a driver, or aren't exposed
+          inside a VM.
+        </p>
+      )}
+
+      <div className="grid grid-cols-1 gap-3 lg:grid-cols-2">
+        {groups.map((group) => (
+          <Card key={group.kind}>
+            <CardHeader className="pb-3">
+              <CardTitle className="text-sm">{group.title}</CardTitle>
+            </CardHeader>
+            <CardContent className="flex flex-col gap-3">
+              {group.sensors.map((sensor) => {
+                const limit = sensor.critical ?? sensor.max;
+                return (
+                  <StatMeter
+                    key={sensor.label}
+                    label={sensor.label}
+                    display={describe(sensor)}
+                    value={sensor.value}
+                    max={limit ?? sensor.value}
+                    color={
+                      sensor.critical !== null &&
+                      sensor.value >= sensor.critical
+                        ? "var(--destructive)"
+                        : group.color
+                    }
+                  />
+                );
+              })}
+            </CardContent>
+          </Card>
+        ))}
+      </div>
+    </div>
+  );
+};
@@ -12,11 +12,49 @@ export type SystemInfo = {
   distribution_id: string;
   cpu_arch: string;
   uptime: number;
+  environment: Environment;
+};
+
+export type Environment = {
+  /** null on bare metal. */
+  hypervisor: string | null;
+  /** null outside a container. */
+  container: string | null;
+  limits: CgroupLimits;
+};
+
+/** null where no cgroup limit is set. */
+export type CgroupLimits = {
+  /** CPUs' worth of time per period. */
+  cpu_quota: number | null;
+  memory_limit: number | null;
 };
 
 export type CacheInfo = {
   label: string;
   bytes: number;
+  level: number;
+  /** Geometry is null when sysfs doesn't report it. */
+  associativity: number | null;
+  line_size: number | null;
+  sets: number | null;
+  partitions: number | null;
+  /** From cpuid, the most CPUs it is designed to be shared by. */
+  shared_by: number;
+  /** cpuid only. */
+  inclusive: boolean | null;
+  fully_associative: boolean;
+};
+
+export type TlbInfo = {
+  /** For legacy leaf 2 descriptors, a description; other fields unknown. */
+  label: string;
+  level: number | null;
+  page_sizes: string[];
+  entries: number | null;
+  associativity: number | null;
+  fully_associative: boolean;
+  shared_by: number | null;
 };
 
 export type CpuStatic = {
@@ -24,7 +62,135 @@ export type CpuStatic = {
   physical_cores: number;
   logical_cores: number;
   cache_sizes: CacheInfo[];
-  features: string[];
+  /** x86 only, and only Intel reports them. */
+  tlbs: TlbInfo[];
+  features: CpuFeature[];
+  /** Each distinct core design; aarch64 Linux only. */
+  core_names: string[];
+  /** Mixes performance and efficiency cores. */
+  hybrid: boolean;
+  /** cpufreq policies; empty outside Linux. */
+  frequency_policies: FrequencyPolicy[];
+  /** Whether turbo frequencies are enabled, if the driver can say. */
+  boost: boolean | null;
+  /** One entry per online logical CPU; empty outside Linux. */
+  topology: LogicalCpu[];
+  /** x86 only. */
+  signature: CpuSignature | null;
+  /** Linux only, like `vulnerabilities`. */
+  microcode: string | null;
+  vulnerabilities: Vulnerability[];
+};
+
+export type FeatureCategory =
+  | "simd"
+  | "crypto"
+  | "virtualization"
+  | "security"
+  | "memory"
+  | "other";
+
+export type CpuFeature = {
+  name: string;
+  category: FeatureCategory;
+  /** False when the OS hasn't enabled the register state it needs. */
+  usable: boolean;
+};
+
+/** Family and model include the extended fields. */
+export type CpuSignature = {
+  vendor: string;
+  family: number;
+  model: number;
+  stepping: number;
+};
+
+export type VulnerabilityStatus =
+  | "not_affected"
+  | "mitigated"
+  | "vulnerable"
+  | "unknown";
+
+export type Vulnerability = {
+  /** e.g. `spectre_v2`. */
+  name: string;
+  status: VulnerabilityStatus;
+  /** The kernel's full report. */
+  detail: string;
+};
+
+/** Mirrors `LogicalCpu` in `src-tauri/src/topology.rs`. */
+export type LogicalCpu = {
+  /** As in `CoreSnapshot.name`. */
+  name: string;
+  id: number;
+  package: number | null;
+  die: number | null;
+  /** Unique within its package only. */
+  core: number | null;
+  /** Hardware threads on the same physical core, including this one. */
+  siblings: number[];
+  numa_node: number | null;
+  caches: CacheInstance[];
+  /** Only set on hybrid parts. */
+  core_type: CoreType | null;
+  /** 0 for the CPUs with the highest max frequency, 1 for the next, … */
+  frequency_class: number | null;
+};
+
+export type CoreType = "performance" | "efficiency";
+
+export type CacheInstance = {
+  /** Matches `CacheInfo.label`. */
+  label: string;
+  instance: number;
+  shared_with: number[];
+};
+
+/** Fixed part of a cpufreq policy; frequencies in MHz. */
+export type FrequencyPolicy = {
+  id: number;
+  cpus: number[];
+  driver: string | null;
+  available_governors: string[];
+  min_frequency: number | null;
+  max_frequency: number | null;
+  base_frequency: number | null;
+  /** Milliseconds at each frequency since boot; may be empty. */
+  time_in_state: { frequency: number; time_ms: number }[];
+};
+
+/** Live part of a core's cpufreq policy; frequencies in MHz. */
+export type FrequencyScaling = {
+  governor: string | null;
+  scaling_min: number | null;
+  scaling_max: number | null;
+};
+
+/** Sampling metadata flattened into every streamed snapshot. */
+export type Tick = {
+  /** Unix time in milliseconds of the refresh behind the snapshot. */
+  timestamp: number;
+  /** Increments by one per snapshot sent to this subscription. */
+  seq: number;
+  /** Milliseconds since the previous snapshot; 0 on the first. */
+  elapsed_ms: number;
+  /** Ticks skipped because the collector fell behind. */
+  missed: number;
+};
+
+/** Percent of the interval spent in each state, from `/proc/stat`. */
+export type CpuTimes = {
+  /** Excluding guest time. */
+  user: number;
+  nice: number;
+  system: number;
+  idle: number;
+  iowait: number;
+  irq: number;
+  softirq: number;
+  steal: number;
+  guest: number;
 };
 
 export type CoreSnapshot = {
@@ -32,14 +198,33 @@ export type CoreSnapshot = {
   usage: number;
   /** Current frequency in MHz. */
   frequency: number;
+  /** Linux only. */
+  times: CpuTimes | null;
+  /** Linux only. */
+  scaling: FrequencyScaling | null;
+  core_type: CoreType | null;
+  frequency_class: number | null;
+  /** Only sent when the stream was started with `idleStates`; Linux only. */
+  idle_states: IdleResidency[] | null;
+};
+
+/** One cpuidle (C-) state of a core. */
+export type IdleResidency = {
+  name: string;
+  /** Exit latency in microseconds. */
+  latency: number;
+  /** Percent of the time since the previous snapshot. */
+  residency: number;
+  disabled: boolean;
 };
 
-export type CpuSnapshot = {
+export type CpuSnapshot = Tick & {
   global_usage: number;
+  global_times: CpuTimes | null;
   cores: CoreSnapshot[];
 };
 
-export type MemorySnapshot = {
+export type MemorySnapshot = Tick & {
   total: number;
   used: number;
   free: number;
@@ -57,11 +242,36 @@ export type ProcessInfo = {
   cpu_usage: number;
   memory: number;
   virtual_memory: number;
-  run_time: number;
+  /** Unix seconds; see `runTime` in use-process-stream. */
+  start_time: number;
   parent: number | null;
   status: string;
 };
 
+/** Fields of a known process that changed since the previous message. */
+export type ProcessChange = { pid: number } & Partial<
+  Pick<
+    ProcessInfo,
+    "cpu_usage" | "memory" | "virtual_memory" | "status"
+  >
+>;
+
+/**
+ * The `processes` stream: a full table first (and after a resync), deltas
+ * after that. `seq` increments by one per message.
+ */
+export type ProcessesMessage = Tick &
+  (
+    | { kind: "snapshot"; processes: ProcessInfo[] }
+    | {
+        kind: "delta";
+        /** New processes and known PIDs whose identity changed. */
+        added: ProcessInfo[];
+        removed: number[];
+        changed: ProcessChange[];
+      }
+  );
+
 export type TopProcess = {
   pid: number;
   name: string;
@@ -70,7 +280,56 @@ export type TopProcess = {
 };
 
 /** Aggregate snapshot for the dashboard and status footer; rates are bytes/sec. */
-export type OverviewSnapshot = {
+/** All zero on Windows. */
+export type LoadAverage = { one: number; five: number; fifteen: number };
+
+/** One line of a `/proc/pressure/*` file. */
+export type StallTime = {
+  /** Percent of the last 10/60/300 seconds spent stalled. */
+  avg10: number;
+  avg60: number;
+  avg300: number;
+  /** Microseconds since boot. */
+  total: number;
+};
+
+export type Pressure = { some: StallTime; full: StallTime | null };
+
+/** Linux PSI; `null` where the kernel doesn't report it. */
+export type PressureReading = {
+  cpu: Pressure | null;
+  memory: Pressure | null;
+  io: Pressure | null;
+};
+
+export type LoadSnapshot = Tick & {
+  load_average: LoadAverage;
+  pressure: PressureReading;
+};
+
+/** One row of `/proc/interrupts` or `/proc/softirqs`, as rates per second. */
+export type IrqRate = {
+  irq: string;
+  /** Controller and device names; empty for softirqs. */
+  device: string;
+  /** In `InterruptsSnapshot.cpus` order; one entry for ERR/MIS. */
+  per_cpu: number[];
+  total: number;
+};
+
+/** Linux only; all zero elsewhere and on the first snapshot. */
+export type InterruptsSnapshot = Tick & {
+  context_switches: number;
+  interrupts: number;
+  forks: number;
+  /** CPU ids of the `per_cpu` columns. */
+  cpus: number[];
+  /** Only IRQs that fired, busiest first. */
+  irqs: IrqRate[];
+  softirqs: IrqRate[];
+};
+
+export type OverviewSnapshot = Tick & {
   cpu_global: number;
   core_usages: number[];
   mem_total: number;
@@ -83,6 +342,8 @@ export type OverviewSnapshot = {
   net_tx: number;
   disk_read: number;
   disk_write: number;
+  load_average: LoadAverage;
+  pressure: PressureReading;
 };
 
 export type DiskInfo = {
@@ -98,6 +359,8 @@ export type DiskInfo = {
   write_rate: number;
 };
 
+export type DisksSnapshot = Tick & { disks: DiskInfo[] };
+
 export type NetworkInfo = {
   name: string;
   /** Bytes/sec since the previous refresh. */
@@ -107,6 +370,84 @@ export type NetworkInfo = {
   total_transmitted: number;
 };
 
+export type NetworksSnapshot = Tick & { interfaces: NetworkInfo[] };
+
+export type SensorKind = "temperature" | "fan" | "voltage";
+
+export type SensorInfo = {
+  kind: SensorKind;
+  label: string;
+  /** °C, RPM or volts depending on `kind`. */
+  value: number;
+  max: number | null;
+  critical: number | null;
+};
+
+export type SensorsSnapshot = Tick & { sensors: SensorInfo[] };
+
+/** Mirrors the `Metric` enum in `src-tauri/src/history.rs`. */
+export type HistoryMetric =
+  | "cpu"
+  | "cpu_cores"
+  | "memory"
+  | "swap"
+  | "network_rx"
+  | "network_tx"
+  | "disk_read"
+  | "disk_write"
+  /** Busiest process names; only in the persistent metric store. */
+  | "process_cpu"
+  | "process_memory";
+
+export type HistoryPoint = {
+  /** Unix time in milliseconds. */
+  timestamp: number;
+  value: number;
+};
+
+export type HistorySeries = {
+  name: string;
+  /** Oldest first. */
+  points: HistoryPoint[];
+};
+
+/** Mirrors the `Resolution` enum in `src-tauri/src/store.rs`. */
+export type MetricResolution = "second" | "minute" | "fifteen_minutes";
+
 /** Mirrors the `StreamName` enum in `src-tauri/src/streams.rs`. */
 export type StreamName =
-  "cpu" | "memory" | "processes" | "overview" | "disks" | "networks";
+  | "cpu"
+  | "memory"
+  | "processes"
+  | "overview"
+  | "disks"
+  | "networks"
+  | "sensors"
+  | "load"
+  | "interrupts";
+
+/** One entry of `get_stream_status`, for debugging leaked or stalled streams. */
+export type StreamStatus = {
+  stream: StreamName;
+  running: boolean;
+  subscribers: number;
+  /** Unix ms when the stream last went from no subscribers to some. */
+  started_at: number | null;
+  ticks: number;
+  last_tick_at: number | null;
+  last_refresh_ms: number | null;
+  last_send_ms: number | null;
+  /** Measured on one snapshot in 16 per subscriber. */
+  last_payload_bytes: number | null;
+  last_error: string | null;
+};
+
+/** Mirrors `Error` in `src-tauri/src/error.rs`; what failed commands reject with. */
+export type CommandError = { message: string } & (
+  | { kind: "not_found"; what: string }
+  | { kind: "permission_denied"; action: string }
+  | { kind: "protected_process"; pid: number }
+  | { kind: "unsupported"; feature: string }
+  | { kind: "stream_cancelled"; stream: StreamName }
+  | { kind: "io"; path: string | null }
+);